@@ -0,0 +1,144 @@
+use std::f32::consts::PI;
+use std::fmt;
+
+use crate::waveforms::SAMPLE_RATE;
+
+/// Enumerates the responses of a [Biquad] used by the equalizer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BiquadResponse {
+    /// Raises or lowers all frequencies below the corner frequency.
+    LowShelf,
+    /// Raises or lowers a band of frequencies around the center frequency.
+    Peaking,
+    /// Raises or lowers all frequencies above the corner frequency.
+    HighShelf
+}
+
+/// Implements the [Display] trait for [BiquadResponse]
+impl fmt::Display for BiquadResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BiquadResponse::LowShelf => write!(f, "LS"),
+            BiquadResponse::Peaking => write!(f, "PK"),
+            BiquadResponse::HighShelf => write!(f, "HS")
+        }
+    }
+}
+
+/// Second-order IIR filter in transposed direct form II, with its coefficients computed after the well known
+/// Audio EQ Cookbook by Robert Bristow-Johnson
+#[derive(Debug)]
+pub struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32
+}
+
+impl Biquad {
+    /// Creates a filter which passes the signal unchanged.
+    pub fn new() -> Self {
+        Biquad { b0: 1.0, b1: 0.0, b2: 0.0, a1: 0.0, a2: 0.0, z1: 0.0, z2: 0.0 }
+    }
+
+    /// Recomputes the coefficients for a response at a frequency in Hz, with a gain in decibels and a quality factor,
+    /// which sets the width of a peaking band and the steepness of a shelf. The filter state is kept, so that the
+    /// settings may be changed while the filter is running.
+    pub fn set_parameters(&mut self, response: BiquadResponse, frequency: f32, gain: f32, q: f32) {
+        let a = 10.0_f32.powf(gain / 40.0);
+        let w0 = 2.0 * PI * frequency.clamp(1.0, 0.49 * SAMPLE_RATE) / SAMPLE_RATE;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * q);
+        let shelf = 2.0 * a.sqrt() * alpha;
+
+        let (b0, b1, b2, a0, a1, a2) = match response {
+            BiquadResponse::LowShelf => (
+                a * ((a + 1.0) - (a - 1.0) * cos + shelf),
+                2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                a * ((a + 1.0) - (a - 1.0) * cos - shelf),
+                (a + 1.0) + (a - 1.0) * cos + shelf,
+                -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                (a + 1.0) + (a - 1.0) * cos - shelf
+            ),
+            BiquadResponse::Peaking => (
+                1.0 + alpha * a,
+                -2.0 * cos,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos,
+                1.0 - alpha / a
+            ),
+            BiquadResponse::HighShelf => (
+                a * ((a + 1.0) + (a - 1.0) * cos + shelf),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                a * ((a + 1.0) + (a - 1.0) * cos - shelf),
+                (a + 1.0) - (a - 1.0) * cos + shelf,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                (a + 1.0) - (a - 1.0) * cos - shelf
+            )
+        };
+
+        // Normalize the coefficients, so that a0 becomes 1.0
+        self.b0 = b0 / a0;
+        self.b1 = b1 / a0;
+        self.b2 = b2 / a0;
+        self.a1 = a1 / a0;
+        self.a2 = a2 / a0;
+    }
+
+    /// Filters a single sample.
+    pub fn process(&mut self, input: f32) -> f32 {
+        let output = self.b0 * input + self.z1;
+        self.z1 = self.b1 * input - self.a1 * output + self.z2;
+        self.z2 = self.b2 * input - self.a2 * output;
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Largest deviation from the expected gain accepted in decibels
+    const TOLERANCE: f32 = 0.1;
+
+    /// Returns the gain of the filter in decibels for a sine at the given frequency, once it has settled.
+    fn gain_at(filter: &mut Biquad, frequency: f32) -> f32 {
+        let samples = SAMPLE_RATE as usize;
+        let peak = (0..samples)
+            .map(|n| filter.process((2.0 * PI * frequency * n as f32 / SAMPLE_RATE).sin()))
+            .skip(samples / 2)
+            .fold(0.0, |peak: f32, sample| peak.max(sample.abs()));
+
+        20.0 * peak.log10()
+    }
+
+    #[test]
+    fn zero_gain_passes_signal_unchanged() {
+        for response in [BiquadResponse::LowShelf, BiquadResponse::Peaking, BiquadResponse::HighShelf] {
+            let mut filter = Biquad::new();
+            filter.set_parameters(response, 1000.0, 0.0, 0.71);
+
+            for n in 0..1000 {
+                let input = (0.37 * n as f32).sin() * 0.5 + (2.9 * n as f32).cos() * 0.3;
+                let output = filter.process(input);
+                assert!((output - input).abs() < 1e-5, "{} changes {} to {}", response, input, output);
+            }
+        }
+    }
+
+    #[test]
+    fn peaking_band_boosts_and_cuts_by_its_gain_at_center_frequency() {
+        for gain in [-12.0, -3.0, 6.0, 15.0] {
+            let mut filter = Biquad::new();
+            filter.set_parameters(BiquadResponse::Peaking, 1000.0, gain, 1.0);
+
+            let measured = gain_at(&mut filter, 1000.0);
+            assert!((measured - gain).abs() < TOLERANCE, "{} dB band measured {} dB", gain, measured);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::effects::{BitcrusherParameters, MAX_CRUSHER_BITS, MAX_CRUSHER_DOWNSAMPLE, MIN_CRUSHER_BITS, StereoEffect};
+use crate::waveforms::AMPLITUDE;
+
+/// Bitcrusher reducing the bit depth and sample rate of the signal, which gives the gritty, aliased sound
+/// of early digital hardware
+#[derive(Debug)]
+pub struct Bitcrusher {
+    step: f32,
+    downsample: u32,
+    mix: f32,
+    counter: u32,
+    held: (f32, f32)
+}
+
+impl Bitcrusher {
+    pub fn new() -> Self {
+        Bitcrusher { step: 0.0, downsample: 1, mix: 0.0, counter: 0, held: (0.0, 0.0) }
+    }
+
+    /// Applies the bit depth, sample rate reduction and mix from the next processed frame onwards.
+    pub fn set_parameters(&mut self, parameters: &BitcrusherParameters) {
+        // The range a single voice spans, [-AMPLITUDE, AMPLITUDE], is divided into as many steps as the bit depth
+        // can represent, so that even the lowest bit depths crush a voice rather than round it to silence
+        let bits = parameters.bits.clamp(MIN_CRUSHER_BITS, MAX_CRUSHER_BITS);
+        self.step = 2.0 * AMPLITUDE / (1u32 << bits) as f32;
+        self.downsample = parameters.downsample.clamp(1, MAX_CRUSHER_DOWNSAMPLE);
+        self.mix = parameters.mix.clamp(0.0, 1.0);
+    }
+
+    /// Rounds a sample to the nearest step of the bit depth.
+    fn quantize(&self, sample: f32) -> f32 {
+        (sample / self.step).round() * self.step
+    }
+}
+
+/// Implementation of the [StereoEffect] trait for the [Bitcrusher]
+impl StereoEffect for Bitcrusher {
+    fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        // Hold every sample for as many frames as the sample rate is divided by
+        if self.counter == 0 {
+            self.held = (self.quantize(left), self.quantize(right));
+        }
+        self.counter = (self.counter + 1) % self.downsample;
+
+        let (crushed_left, crushed_right) = self.held;
+        (left * (1.0 - self.mix) + crushed_left * self.mix, right * (1.0 - self.mix) + crushed_right * self.mix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use crate::effects::EffectsParameters;
+
+    use super::*;
+
+    #[test]
+    fn low_bit_depths_crush_a_voice_instead_of_silencing_it() {
+        for bits in [MIN_CRUSHER_BITS, 2] {
+            let mut bitcrusher = Bitcrusher::new();
+            bitcrusher.set_parameters(&BitcrusherParameters { mix: 1.0, bits, downsample: 1, ..EffectsParameters::default().bitcrusher });
+
+            let peak = (0..100)
+                .map(|n| AMPLITUDE * (TAU * n as f32 / 100.0).sin())
+                .map(|sample| bitcrusher.process(sample, sample).0.abs())
+                .fold(0.0, f32::max);
+
+            assert!(peak >= 0.5 * AMPLITUDE, "{} bits crush a sine at voice level to a peak of {}", bits, peak);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use crate::effects::{ChorusParameters, MAX_CHORUS_RATE, MIN_CHORUS_RATE, StereoEffect};
+use crate::effects::delay_line::DelayLine;
+use crate::waveforms::SAMPLE_RATE;
+use crate::waveforms::sine_wave::calculate_sine;
+
+/// Delay in seconds around which the chorus sweeps, long enough to be heard as a second voice
+const CHORUS_DELAY: f32 = 0.015;
+
+/// Delay in seconds the chorus sweeps by in either direction at full depth
+const CHORUS_SWEEP: f32 = 0.007;
+
+/// Delay in seconds around which the flanger sweeps, short enough to be heard as a comb filter
+const FLANGER_DELAY: f32 = 0.0025;
+
+/// Delay in seconds the flanger sweeps by in either direction at full depth
+const FLANGER_SWEEP: f32 = 0.002;
+
+/// Share of the delayed signal fed back by the flanger, which sharpens the notches of the comb filter
+const FLANGER_FEEDBACK: f32 = 0.7;
+
+/// Chorus and flanger mixing the signal with a copy delayed by a slowly swept time.
+///
+/// The sweep of the right channel lags a quarter period behind the left one, which spreads the effect across
+/// the stereo field.
+#[derive(Debug)]
+pub struct Chorus {
+    lines: [DelayLine; 2],
+    phase: f32,
+    parameters: ChorusParameters
+}
+
+impl Chorus {
+    pub fn new() -> Self {
+        let length = ((CHORUS_DELAY + CHORUS_SWEEP) * SAMPLE_RATE) as usize + 2;
+        let parameters = ChorusParameters { enabled: false, mix: 0.0, rate: MIN_CHORUS_RATE, depth: 0.0, flanger: false };
+        Chorus { lines: [DelayLine::new(length), DelayLine::new(length)], phase: 0.0, parameters }
+    }
+
+    /// Applies the chorus settings from the next processed frame onwards.
+    pub fn set_parameters(&mut self, parameters: &ChorusParameters) {
+        self.parameters = *parameters;
+    }
+}
+
+/// Implementation of the [StereoEffect] trait for the [Chorus]
+impl StereoEffect for Chorus {
+    fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let ChorusParameters { mix, rate, depth, flanger, .. } = self.parameters;
+        let (center, sweep, feedback) = if flanger {
+            (FLANGER_DELAY, FLANGER_SWEEP, FLANGER_FEEDBACK)
+        } else {
+            (CHORUS_DELAY, CHORUS_SWEEP, 0.0)
+        };
+
+        let sweep = sweep * depth.clamp(0.0, 1.0);
+        let delay_left = (center + sweep * calculate_sine(self.phase)) * SAMPLE_RATE;
+        let delay_right = (center + sweep * calculate_sine((self.phase + 0.25).fract())) * SAMPLE_RATE;
+
+        let wet_left = self.lines[0].read(delay_left);
+        let wet_right = self.lines[1].read(delay_right);
+
+        self.lines[0].write(left + wet_left * feedback);
+        self.lines[1].write(right + wet_right * feedback);
+
+        self.phase = (self.phase + rate.clamp(MIN_CHORUS_RATE, MAX_CHORUS_RATE) / SAMPLE_RATE).fract();
+
+        (left * (1.0 - mix) + wet_left * mix, right * (1.0 - mix) + wet_right * mix)
+    }
+}
@@ -0,0 +1,191 @@
+use crate::effects::{CompressorParameters, EffectsParameters, MAX_COMPRESSOR_MAKEUP, MAX_COMPRESSOR_RATIO, MAX_COMPRESSOR_TIME, MIN_COMPRESSOR_THRESHOLD,
+                     MIN_COMPRESSOR_TIME, Processed, SidechainMode, StereoEffect};
+use crate::waveforms::SAMPLE_RATE;
+
+/// Level in decibels treated as silence, keeping the logarithm of the detected level finite
+const SILENCE: f32 = -120.0;
+
+/// Returns the coefficient of a one-pole smoother which covers about two thirds of a step within the given time in seconds.
+fn smoothing_coefficient(time: f32) -> f32 {
+    (-1.0 / (time.clamp(MIN_COMPRESSOR_TIME, MAX_COMPRESSOR_TIME) * SAMPLE_RATE)).exp()
+}
+
+/// [rodio::Source] adapter compressing a stereo or mono source, which is how the compressor follows the master bus
+pub type Compressed<S> = Processed<S, Compressor>;
+
+/// Feed-forward compressor reducing the level of both channels together once it rises above the threshold.
+///
+/// In sidechain mode the compressor ignores the level of the signal and reacts to a key instead, which jumps to
+/// full scale whenever [Compressor::trigger] is called and fades out over the release time. The signal is thereby
+/// ducked on every trigger and swells back afterwards, which gives the well known pumping effect. The signal passes
+/// unchanged while the compressor is bypassed.
+#[derive(Debug)]
+pub struct Compressor {
+    parameters: CompressorParameters,
+    attack: f32,
+    release: f32,
+    makeup: f32,
+    key: f32,
+    reduction: f32
+}
+
+impl Compressor {
+    pub fn new() -> Self {
+        let parameters = EffectsParameters::default().compressor;
+        let mut compressor = Compressor { parameters, attack: 0.0, release: 0.0, makeup: 1.0, key: 0.0, reduction: 0.0 };
+        compressor.set_parameters(&parameters);
+        compressor
+    }
+
+    /// Applies the compressor settings from the next processed frame onwards.
+    pub fn set_parameters(&mut self, parameters: &CompressorParameters) {
+        self.parameters = CompressorParameters {
+            threshold: parameters.threshold.clamp(MIN_COMPRESSOR_THRESHOLD, 0.0),
+            ratio: parameters.ratio.clamp(1.0, MAX_COMPRESSOR_RATIO),
+            ..*parameters
+        };
+        self.attack = smoothing_coefficient(parameters.attack);
+        self.release = smoothing_coefficient(parameters.release);
+        self.makeup = 10.0_f32.powf(parameters.makeup.clamp(0.0, MAX_COMPRESSOR_MAKEUP) / 20.0);
+
+        // A bypassed compressor is not processed, so drop the key and reduction rather than let them linger until it is enabled
+        if !parameters.enabled {
+            self.key = 0.0;
+            self.reduction = 0.0;
+        }
+    }
+
+    /// Keys the compressor if it is enabled and its sidechain listens to the given source,
+    /// ducking the signal from the next frame onwards.
+    pub fn trigger(&mut self, source: SidechainMode) {
+        if self.parameters.enabled && source != SidechainMode::Off && source == self.parameters.sidechain {
+            self.key = 1.0;
+        }
+    }
+}
+
+/// Implementation of the [StereoEffect] trait for the [Compressor]
+impl StereoEffect for Compressor {
+    fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let CompressorParameters { enabled, mix, threshold, ratio, sidechain, .. } = self.parameters;
+        if !enabled {
+            return (left, right);
+        }
+
+        // Detect the level of the key in sidechain mode, and the peak of both channels otherwise
+        let detected = match sidechain {
+            SidechainMode::Off => left.abs().max(right.abs()),
+            _ => {
+                let key = self.key;
+                self.key *= self.release;
+                key
+            }
+        };
+        let level = if detected > 0.0 { (20.0 * detected.log10()).max(SILENCE) } else { SILENCE };
+
+        // Reduce the level above the threshold by the ratio, moving towards the target at the attack or release rate
+        let target = (level - threshold).max(0.0) * (1.0 - 1.0 / ratio);
+        let coefficient = if target > self.reduction { self.attack } else { self.release };
+        self.reduction = target + (self.reduction - target) * coefficient;
+
+        let gain = 10.0_f32.powf(-self.reduction / 20.0) * self.makeup;
+        (left * (1.0 - mix) + left * gain * mix, right * (1.0 - mix) + right * gain * mix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Level of the constant signal fed into the compressor
+    const INPUT: f32 = 0.25;
+
+    /// Returns the settings of an enabled compressor ducking the signal on every note by up to 19 dB.
+    fn sidechained(release: f32) -> CompressorParameters {
+        CompressorParameters {
+            enabled: true,
+            mix: 1.0,
+            threshold: -20.0,
+            ratio: MAX_COMPRESSOR_RATIO,
+            attack: MIN_COMPRESSOR_TIME,
+            release,
+            makeup: 0.0,
+            sidechain: SidechainMode::Notes
+        }
+    }
+
+    /// Returns the gain in decibels the compressor applies to the next frame of the constant signal.
+    fn next_gain(compressor: &mut Compressor) -> f32 {
+        let (left, right) = compressor.process(INPUT, -INPUT);
+        assert_eq!(left, -right);
+        20.0 * (left / INPUT).log10()
+    }
+
+    /// Triggers the compressor and returns the number of frames it takes the gain to recover to within 1 dB.
+    fn frames_to_recover(release: f32) -> usize {
+        let mut compressor = Compressor::new();
+        compressor.set_parameters(&sidechained(release));
+        compressor.trigger(SidechainMode::Notes);
+
+        // Let the gain fall to its lowest point before timing its recovery
+        let mut frames = 0;
+        let mut gain = next_gain(&mut compressor);
+        loop {
+            frames += 1;
+            let next = next_gain(&mut compressor);
+            if next > gain {
+                break;
+            }
+            gain = next;
+        }
+
+        (frames..).find(|_| next_gain(&mut compressor) > -1.0).unwrap()
+    }
+
+    #[test]
+    fn trigger_pulls_gain_down() {
+        let mut compressor = Compressor::new();
+        compressor.set_parameters(&sidechained(0.1));
+
+        assert_eq!(next_gain(&mut compressor), 0.0);
+
+        compressor.trigger(SidechainMode::Notes);
+        let lowest = (0..(0.01 * SAMPLE_RATE) as usize).map(|_| next_gain(&mut compressor)).fold(0.0, f32::min);
+        assert!(lowest < -15.0, "the gain only fell to {} dB", lowest);
+
+        // Triggers of another source leave the gain alone
+        let mut compressor = Compressor::new();
+        compressor.set_parameters(&sidechained(0.1));
+        compressor.trigger(SidechainMode::Tempo);
+        assert_eq!(next_gain(&mut compressor), 0.0);
+    }
+
+    #[test]
+    fn gain_recovers_at_release_rate() {
+        let fast = frames_to_recover(0.05);
+        let slow = frames_to_recover(0.1);
+
+        // The gain recovers within a few release times, taking twice as long at twice the release time
+        assert!(fast as f32 / SAMPLE_RATE < 10.0 * 0.05, "recovered in {} frames", fast);
+        let ratio = slow as f32 / fast as f32;
+        assert!((1.9..2.1).contains(&ratio), "recovered in {} and {} frames", fast, slow);
+    }
+
+    #[test]
+    fn bypassed_compressor_passes_signal_unchanged_and_ignores_triggers() {
+        let mut compressor = Compressor::new();
+        compressor.set_parameters(&CompressorParameters { enabled: false, makeup: 12.0, ..sidechained(0.1) });
+
+        for n in 0..1000 {
+            if n % 100 == 0 {
+                compressor.trigger(SidechainMode::Notes);
+            }
+            let (left, right) = ((0.37 * n as f32).sin(), (2.9 * n as f32).cos());
+            assert_eq!(compressor.process(left, right), (left, right));
+        }
+
+        // Once enabled, no key is left over from the triggers received while bypassed
+        compressor.set_parameters(&sidechained(0.1));
+        assert_eq!(next_gain(&mut compressor), 0.0);
+    }
+}
@@ -0,0 +1,39 @@
+use crate::effects::{DelayParameters, MAX_DELAY_FEEDBACK, MAX_DELAY_TIME, StereoEffect};
+use crate::effects::delay_line::DelayLine;
+use crate::waveforms::SAMPLE_RATE;
+
+/// Stereo delay repeating each channel after the delay time, with every echo fed back to produce the next one
+#[derive(Debug)]
+pub struct Delay {
+    lines: [DelayLine; 2],
+    delay: f32,
+    feedback: f32,
+    mix: f32
+}
+
+impl Delay {
+    pub fn new() -> Self {
+        let length = (MAX_DELAY_TIME * SAMPLE_RATE) as usize + 2;
+        Delay { lines: [DelayLine::new(length), DelayLine::new(length)], delay: SAMPLE_RATE, feedback: 0.0, mix: 0.0 }
+    }
+
+    /// Applies the delay settings, where a tempo-synced delay time follows from the tempo in beats per minute.
+    pub fn set_parameters(&mut self, parameters: &DelayParameters, tempo: f32) {
+        self.delay = parameters.seconds(tempo) * SAMPLE_RATE;
+        self.feedback = parameters.feedback.clamp(0.0, MAX_DELAY_FEEDBACK);
+        self.mix = parameters.mix.clamp(0.0, 1.0);
+    }
+}
+
+/// Implementation of the [StereoEffect] trait for the [Delay]
+impl StereoEffect for Delay {
+    fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let echo_left = self.lines[0].read(self.delay);
+        let echo_right = self.lines[1].read(self.delay);
+
+        self.lines[0].write(left + echo_left * self.feedback);
+        self.lines[1].write(right + echo_right * self.feedback);
+
+        (left * (1.0 - self.mix) + echo_left * self.mix, right * (1.0 - self.mix) + echo_right * self.mix)
+    }
+}
@@ -0,0 +1,32 @@
+/// Circular buffer delaying a signal by a fractional number of samples, read with linear interpolation
+#[derive(Debug)]
+pub struct DelayLine {
+    buffer: Vec<f32>,
+    position: usize
+}
+
+impl DelayLine {
+    /// Creates a silent delay line holding up to the given number of samples.
+    pub fn new(length: usize) -> Self {
+        DelayLine { buffer: vec![0.0; length.max(2)], position: 0 }
+    }
+
+    /// Returns the sample written the given number of samples ago, interpolating between neighbouring samples.
+    /// The delay is kept within the length of the line.
+    pub fn read(&self, delay: f32) -> f32 {
+        let length = self.buffer.len();
+        let delay = delay.clamp(1.0, (length - 1) as f32);
+        let fraction = delay.fract();
+
+        let newer = (self.position + length - delay as usize) % length;
+        let older = (newer + length - 1) % length;
+
+        self.buffer[newer] * (1.0 - fraction) + self.buffer[older] * fraction
+    }
+
+    /// Writes the next sample, overwriting the oldest one.
+    pub fn write(&mut self, sample: f32) {
+        self.buffer[self.position] = sample;
+        self.position = (self.position + 1) % self.buffer.len();
+    }
+}
@@ -0,0 +1,51 @@
+use crate::effects::{DistortionParameters, DistortionShape, MAX_DISTORTION_DRIVE, StereoEffect};
+use crate::waveforms::AMPLITUDE;
+
+/// Waveshaping distortion driving the signal into a clipping or folding curve.
+///
+/// The curve is applied relative to the peak level of a single voice, [AMPLITUDE], so that the drive rather than
+/// the number of sounding voices decides how much the signal distorts, and the distorted signal stays about as loud
+/// as a clean voice.
+#[derive(Debug)]
+pub struct Distortion {
+    gain: f32,
+    mix: f32,
+    shape: DistortionShape
+}
+
+impl Distortion {
+    pub fn new() -> Self {
+        Distortion { gain: 1.0, mix: 0.0, shape: DistortionShape::SoftClip }
+    }
+
+    /// Applies the drive, shape and mix from the next processed frame onwards.
+    pub fn set_parameters(&mut self, parameters: &DistortionParameters) {
+        self.gain = 10.0_f32.powf(parameters.drive.clamp(0.0, MAX_DISTORTION_DRIVE) / 20.0);
+        self.mix = parameters.mix.clamp(0.0, 1.0);
+        self.shape = parameters.shape;
+    }
+
+    /// Drives a single sample into the shaping curve.
+    fn shape(&self, sample: f32) -> f32 {
+        let driven = sample * self.gain / AMPLITUDE;
+
+        let shaped = match self.shape {
+            DistortionShape::SoftClip => driven.tanh(),
+            DistortionShape::HardClip => driven.clamp(-1.0, 1.0),
+            // Reflect the signal off -1.0 and 1.0 until it lies within range, which is a triangle wave of the input
+            DistortionShape::Foldback => {
+                let phase = (driven + 1.0).rem_euclid(4.0);
+                if phase < 2.0 { phase - 1.0 } else { 3.0 - phase }
+            }
+        };
+
+        shaped * AMPLITUDE
+    }
+}
+
+/// Implementation of the [StereoEffect] trait for the [Distortion]
+impl StereoEffect for Distortion {
+    fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        (left * (1.0 - self.mix) + self.shape(left) * self.mix, right * (1.0 - self.mix) + self.shape(right) * self.mix)
+    }
+}
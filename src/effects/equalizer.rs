@@ -0,0 +1,116 @@
+use crate::effects::{EQ_BANDS, EqParameters, MAX_EQ_FREQUENCY, MAX_EQ_GAIN, MAX_EQ_Q, MIN_EQ_FREQUENCY, MIN_EQ_Q, Processed, StereoEffect};
+use crate::effects::biquad::{Biquad, BiquadResponse};
+
+/// [rodio::Source] adapter equalizing a stereo or mono source, which is how the equalizer follows the compressor
+pub type Equalized<S> = Processed<S, Equalizer>;
+
+/// Parametric equalizer with a low shelf, two peaking bands and a high shelf, in this order, for both channels.
+/// The signal passes unchanged while the equalizer is bypassed.
+#[derive(Debug)]
+pub struct Equalizer {
+    parameters: Option<EqParameters>,
+    filters: [[Biquad; 2]; EQ_BANDS]
+}
+
+impl Equalizer {
+    pub fn new() -> Self {
+        Equalizer { parameters: None, filters: std::array::from_fn(|_| [Biquad::new(), Biquad::new()]) }
+    }
+
+    /// Returns the response of the band at the given index, where the outermost bands are shelves.
+    pub fn band_response(index: usize) -> BiquadResponse {
+        match index {
+            0 => BiquadResponse::LowShelf,
+            index if index == EQ_BANDS - 1 => BiquadResponse::HighShelf,
+            _ => BiquadResponse::Peaking
+        }
+    }
+
+    /// Applies the settings of the bands from the next processed frame onwards. The coefficients are only
+    /// recomputed if the settings have changed.
+    pub fn set_parameters(&mut self, parameters: &EqParameters) {
+        if self.parameters.as_ref() == Some(parameters) {
+            return;
+        }
+
+        for (index, (band, filters)) in parameters.bands.iter().zip(self.filters.iter_mut()).enumerate() {
+            let frequency = band.frequency.clamp(MIN_EQ_FREQUENCY, MAX_EQ_FREQUENCY);
+            let gain = band.gain.clamp(-MAX_EQ_GAIN, MAX_EQ_GAIN);
+            let q = band.q.clamp(MIN_EQ_Q, MAX_EQ_Q);
+
+            for filter in filters.iter_mut() {
+                filter.set_parameters(Equalizer::band_response(index), frequency, gain, q);
+            }
+        }
+
+        self.parameters = Some(*parameters);
+    }
+}
+
+/// Implementation of the [StereoEffect] trait for the [Equalizer]
+impl StereoEffect for Equalizer {
+    fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        if !self.parameters.is_some_and(|parameters| parameters.enabled) {
+            return (left, right);
+        }
+
+        self.filters.iter_mut().fold((left, right), |(left, right), [left_filter, right_filter]| {
+            (left_filter.process(left), right_filter.process(right))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use crate::effects::EqBand;
+    use crate::waveforms::SAMPLE_RATE;
+
+    use super::*;
+
+    /// Largest deviation from the expected gain accepted in decibels
+    const TOLERANCE: f32 = 0.1;
+
+    /// Returns an enabled equalizer whose bands are all flat, except for the given bands.
+    fn equalizer_with(bands: &[(usize, EqBand)]) -> Equalizer {
+        let mut parameters = EqParameters { enabled: true, ..EqParameters::default() };
+        for &(index, band) in bands {
+            parameters.bands[index] = band;
+        }
+
+        let mut equalizer = Equalizer::new();
+        equalizer.set_parameters(&parameters);
+        equalizer
+    }
+
+    #[test]
+    fn flat_bands_pass_signal_unchanged() {
+        let mut equalizer = equalizer_with(&[]);
+
+        for n in 0..1000 {
+            let (left, right) = ((0.37 * n as f32).sin() * 0.5, (2.9 * n as f32).cos() * 0.3);
+            let (equalized_left, equalized_right) = equalizer.process(left, right);
+            assert!((equalized_left - left).abs() < 1e-5 && (equalized_right - right).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn peaking_band_sets_gain_at_its_center_frequency() {
+        for gain in [-9.0, 9.0] {
+            let mut equalizer = equalizer_with(&[(2, EqBand { frequency: 2000.0, gain, q: 2.0 })]);
+
+            let samples = SAMPLE_RATE as usize;
+            let (left, right) = (0..samples)
+                .map(|n| (2.0 * PI * 2000.0 * n as f32 / SAMPLE_RATE).sin())
+                .map(|sample| equalizer.process(sample, -sample))
+                .skip(samples / 2)
+                .fold((0.0, 0.0), |(left, right): (f32, f32), (l, r)| (left.max(l.abs()), right.max(r.abs())));
+
+            for peak in [left, right] {
+                let measured = 20.0 * peak.log10();
+                assert!((measured - gain).abs() < TOLERANCE, "{} dB band measured {} dB", gain, measured);
+            }
+        }
+    }
+}
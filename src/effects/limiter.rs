@@ -0,0 +1,108 @@
+use crate::effects::{Processed, StereoEffect};
+use crate::waveforms::SAMPLE_RATE;
+
+/// Highest peak level the limiter lets through, about -1 dBFS, leaving headroom below the clipping point of the output
+const LIMITER_THRESHOLD: f32 = 0.89;
+
+/// Time in seconds the gain takes to recover by about two thirds once the peaks have subsided
+const LIMITER_RELEASE: f32 = 0.1;
+
+/// [rodio::Source] adapter limiting a stereo or mono source, which is how the limiter ends the output
+pub type Limited<S> = Processed<S, Limiter>;
+
+/// Peak limiter protecting the output from digital clipping, however many voices sum up.
+///
+/// The limiter follows the peaks of both channels together, so that the stereo image does not shift. It reacts
+/// to a rising peak within the same sample, which guarantees that no sample exceeds the threshold, and releases
+/// the gain smoothly afterwards to avoid pumping.
+#[derive(Debug)]
+pub struct Limiter {
+    envelope: f32,
+    release: f32,
+    gain: f32
+}
+
+impl Limiter {
+    pub fn new() -> Self {
+        Limiter { envelope: 0.0, release: (-1.0 / (LIMITER_RELEASE * SAMPLE_RATE)).exp(), gain: 1.0 }
+    }
+
+    /// Returns the gain reduction applied to the most recent frame in decibels, which is 0.0 while the signal
+    /// stays below the threshold.
+    pub fn gain_reduction(&self) -> f32 {
+        20.0 * (1.0 / self.gain).log10()
+    }
+}
+
+/// Implementation of the [StereoEffect] trait for the [Limiter]
+impl StereoEffect for Limiter {
+    fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let peak = left.abs().max(right.abs());
+        self.envelope = if peak > self.envelope { peak } else { peak + (self.envelope - peak) * self.release };
+
+        self.gain = if self.envelope > LIMITER_THRESHOLD { LIMITER_THRESHOLD / self.envelope } else { 1.0 };
+
+        // The gain may round the peak to just above the threshold, which the clamp takes off again
+        (
+            (left * self.gain).clamp(-LIMITER_THRESHOLD, LIMITER_THRESHOLD),
+            (right * self.gain).clamp(-LIMITER_THRESHOLD, LIMITER_THRESHOLD)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::{BLOCK_SIZE, MAX_VOICES};
+    use crate::engine::mixer::Mixer;
+    use crate::engine::voice::Voice;
+    use crate::envelopes::adsr::AdsrParameters;
+    use crate::filters::{FilterMode, FilterParameters, MAX_RESONANCE};
+    use crate::waveforms::sawtooth_wave::SawtoothWave;
+    use crate::waveforms::sine_wave::SineWave;
+    use crate::waveforms::STEREO;
+
+    use super::*;
+
+    /// Number of rendered blocks, which is about a second
+    const BLOCKS: usize = 750;
+
+    /// Plays a sawtooth voice at full velocity for each frequency through the filter, passes the mixed output through
+    /// a limiter and asserts that the mix exceeds the threshold while not a single limited sample does.
+    fn assert_limited(filter: FilterParameters, frequencies: &[f32]) {
+        let mut mixer = Mixer::new(MAX_VOICES);
+        mixer.set_filter(filter);
+        for &frequency in frequencies {
+            let layers = vec![(Box::new(SawtoothWave::new(frequency)) as _, Box::new(SawtoothWave::new(frequency)) as _)];
+            let sub_noise = (Box::new(SineWave::new(frequency)) as _, Box::new(SineWave::new(frequency)) as _);
+            mixer.allocate(Voice::new(layers, sub_noise, frequency, AdsrParameters::default(), 0.0, 1.0));
+        }
+
+        let mut limiter = Limiter::new();
+        let mut buffer = [0.0; BLOCK_SIZE * STEREO as usize];
+        let mut peak: f32 = 0.0;
+
+        for _ in 0..BLOCKS {
+            mixer.render(&mut buffer);
+            for frame in buffer.chunks_exact(STEREO as usize) {
+                peak = peak.max(frame[0].abs()).max(frame[1].abs());
+
+                let (left, right) = limiter.process(frame[0], frame[1]);
+                assert!(left.abs() <= LIMITER_THRESHOLD && right.abs() <= LIMITER_THRESHOLD, "limited to {} {}", left, right);
+            }
+        }
+
+        assert!(peak > LIMITER_THRESHOLD, "the mix peaks at {} only", peak);
+    }
+
+    #[test]
+    fn limits_summed_full_scale_voices() {
+        let filter = FilterParameters { mode: FilterMode::Off, cutoff: 1000.0, resonance: 0.0, envelope: AdsrParameters::default(), envelope_amount: 0.0 };
+        assert_limited(filter, &[110.0; MAX_VOICES]);
+    }
+
+    #[test]
+    fn limits_resonance_peaks() {
+        let filter = FilterParameters { mode: FilterMode::LowPass, cutoff: 440.0, resonance: MAX_RESONANCE, envelope: AdsrParameters::default(), envelope_amount: 0.0 };
+        assert_limited(filter, &[55.0, 110.0, 146.8, 220.0, 293.7, 440.0, 587.3, 880.0]);
+    }
+}
@@ -0,0 +1,505 @@
+use std::fmt;
+use std::time::Duration;
+
+use rodio::Source;
+
+use crate::modulation::LFO_DIVISIONS;
+use crate::waveforms::STEREO;
+
+pub mod biquad;
+pub mod bitcrusher;
+pub mod chorus;
+pub mod compressor;
+pub mod delay;
+pub mod delay_line;
+pub mod distortion;
+pub mod equalizer;
+pub mod limiter;
+pub mod reverb;
+pub mod stereo_width;
+
+/// Default width of the stereo field, which leaves the signal unchanged
+pub const DEFAULT_STEREO_WIDTH: f32 = 1.0;
+
+/// Maximum width of the stereo field, at which the difference between the channels is doubled
+pub const MAX_STEREO_WIDTH: f32 = 2.0;
+
+/// Number of effects in the master effects chain, which the compressor follows
+pub const EFFECT_COUNT: usize = 5;
+
+/// Shortest free-running delay time in seconds
+pub const MIN_DELAY_TIME: f32 = 0.01;
+
+/// Longest delay time in seconds, which also bounds tempo-synced delays at slow tempos
+pub const MAX_DELAY_TIME: f32 = 2.0;
+
+/// Highest feedback of the delay, below 1.0 so that the echoes always die away
+pub const MAX_DELAY_FEEDBACK: f32 = 0.95;
+
+/// Slowest rate of the chorus in Hz
+pub const MIN_CHORUS_RATE: f32 = 0.05;
+
+/// Fastest rate of the chorus in Hz
+pub const MAX_CHORUS_RATE: f32 = 5.0;
+
+/// Highest drive of the distortion in decibels
+pub const MAX_DISTORTION_DRIVE: f32 = 36.0;
+
+/// Lowest bit depth of the bitcrusher, which rounds a voice to the three levels -[crate::waveforms::AMPLITUDE], 0.0 and [crate::waveforms::AMPLITUDE]
+pub const MIN_CRUSHER_BITS: u32 = 1;
+
+/// Highest bit depth of the bitcrusher, at which the quantization is no longer audible
+pub const MAX_CRUSHER_BITS: u32 = 16;
+
+/// Highest factor by which the bitcrusher divides the sample rate
+pub const MAX_CRUSHER_DOWNSAMPLE: u32 = 32;
+
+/// Lowest threshold of the compressor in decibels
+pub const MIN_COMPRESSOR_THRESHOLD: f32 = -60.0;
+
+/// Highest ratio of the compressor, at which it acts almost like a limiter
+pub const MAX_COMPRESSOR_RATIO: f32 = 20.0;
+
+/// Shortest attack and release time of the compressor in seconds
+pub const MIN_COMPRESSOR_TIME: f32 = 0.001;
+
+/// Longest attack and release time of the compressor in seconds
+pub const MAX_COMPRESSOR_TIME: f32 = 2.0;
+
+/// Highest makeup gain of the compressor in decibels
+pub const MAX_COMPRESSOR_MAKEUP: f32 = 24.0;
+
+/// Number of bands of the equalizer, which are a low shelf, two peaking bands and a high shelf
+pub const EQ_BANDS: usize = 4;
+
+/// Lowest frequency of an equalizer band in Hz
+pub const MIN_EQ_FREQUENCY: f32 = 20.0;
+
+/// Highest frequency of an equalizer band in Hz
+pub const MAX_EQ_FREQUENCY: f32 = 20000.0;
+
+/// Highest boost or cut of an equalizer band in decibels
+pub const MAX_EQ_GAIN: f32 = 15.0;
+
+/// Lowest quality factor of an equalizer band, i.e. its widest setting
+pub const MIN_EQ_Q: f32 = 0.3;
+
+/// Highest quality factor of an equalizer band, i.e. its narrowest setting
+pub const MAX_EQ_Q: f32 = 10.0;
+
+/// An effect on the master bus processing the left and right channel together
+pub trait StereoEffect: Send {
+    /// Processes a single stereo frame and returns the resulting left and right sample.
+    fn process(&mut self, left: f32, right: f32) -> (f32, f32);
+}
+
+/// [Source] adapter passing the frames of a stereo source through a [StereoEffect].
+///
+/// This lets any effect process a rodio source in the same way the master bus processes its blocks. The frames of
+/// a mono source are fed to both channels of the effect, keeping the left channel of the result.
+pub struct Processed<S, E> {
+    source: S,
+    effect: E,
+    right: Option<f32>
+}
+
+impl<S, E> Processed<S, E> {
+    pub fn new(source: S, effect: E) -> Self {
+        Processed { source, effect, right: None }
+    }
+
+    /// Returns a mutable reference to the source being processed.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    /// Returns a reference to the effect processing the source.
+    pub fn effect(&self) -> &E {
+        &self.effect
+    }
+
+    /// Returns a mutable reference to the effect processing the source.
+    pub fn effect_mut(&mut self) -> &mut E {
+        &mut self.effect
+    }
+}
+
+/// Implementation of the [Iterator] trait for the [Processed] source
+impl<S: Source<Item = f32>, E: StereoEffect> Iterator for Processed<S, E> {
+    type Item = f32;
+    fn next(&mut self) -> Option<f32> {
+        // Hand out the right sample of the frame processed along with the previous left sample
+        if let Some(right) = self.right.take() {
+            return Some(right);
+        }
+
+        let left = self.source.next()?;
+        if self.source.channels() < STEREO {
+            return Some(self.effect.process(left, left).0);
+        }
+
+        let (left, right) = self.effect.process(left, self.source.next()?);
+        self.right = Some(right);
+
+        Some(left)
+    }
+}
+
+/// Implementation of the [Source] trait for the [Processed] source
+impl<S: Source<Item = f32>, E: StereoEffect> Source for Processed<S, E> {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
+
+/// Enumerates the effects of the master bus, which are the effects of the chain and the compressor following it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EffectKind {
+    Delay,
+    Reverb,
+    Chorus,
+    Distortion,
+    Bitcrusher,
+    Compressor
+}
+
+impl EffectKind {
+    /// Returns the next effect in the order delay, reverb, chorus, distortion, bitcrusher and compressor.
+    pub fn next(&self) -> EffectKind {
+        match self {
+            EffectKind::Delay => EffectKind::Reverb,
+            EffectKind::Reverb => EffectKind::Chorus,
+            EffectKind::Chorus => EffectKind::Distortion,
+            EffectKind::Distortion => EffectKind::Bitcrusher,
+            EffectKind::Bitcrusher => EffectKind::Compressor,
+            EffectKind::Compressor => EffectKind::Delay
+        }
+    }
+}
+
+/// Implements the [Display] trait for [EffectKind]
+impl fmt::Display for EffectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EffectKind::Delay => write!(f, "DLY"),
+            EffectKind::Reverb => write!(f, "REV"),
+            EffectKind::Chorus => write!(f, "CHO"),
+            EffectKind::Distortion => write!(f, "DST"),
+            EffectKind::Bitcrusher => write!(f, "BIT"),
+            EffectKind::Compressor => write!(f, "CMP")
+        }
+    }
+}
+
+/// Enumerates the effects of the master effects chain, whose order may be changed. The compressor is not one of them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChainEffect {
+    Delay,
+    Reverb,
+    Chorus,
+    Distortion,
+    Bitcrusher
+}
+
+impl ChainEffect {
+    /// Returns the effect of the chain the given effect of the master bus is, or none for the compressor.
+    pub fn of(kind: EffectKind) -> Option<ChainEffect> {
+        match kind {
+            EffectKind::Delay => Some(ChainEffect::Delay),
+            EffectKind::Reverb => Some(ChainEffect::Reverb),
+            EffectKind::Chorus => Some(ChainEffect::Chorus),
+            EffectKind::Distortion => Some(ChainEffect::Distortion),
+            EffectKind::Bitcrusher => Some(ChainEffect::Bitcrusher),
+            EffectKind::Compressor => None
+        }
+    }
+}
+
+/// Implements the [From] trait to convert a [ChainEffect] into the [EffectKind] it is selected and shown as
+impl From<ChainEffect> for EffectKind {
+    fn from(effect: ChainEffect) -> Self {
+        match effect {
+            ChainEffect::Delay => EffectKind::Delay,
+            ChainEffect::Reverb => EffectKind::Reverb,
+            ChainEffect::Chorus => EffectKind::Chorus,
+            ChainEffect::Distortion => EffectKind::Distortion,
+            ChainEffect::Bitcrusher => EffectKind::Bitcrusher
+        }
+    }
+}
+
+/// Enumerates the curves the distortion shapes the driven signal with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DistortionShape {
+    /// Rounds off the peaks smoothly, like an overdriven amplifier.
+    SoftClip,
+    /// Cuts off the peaks flat, adding harsh odd harmonics.
+    HardClip,
+    /// Folds the peaks back into range, adding ever more harmonics as the drive rises.
+    Foldback
+}
+
+impl DistortionShape {
+    /// Returns the next shape in the order soft clip, hard clip and foldback.
+    pub fn next(&self) -> DistortionShape {
+        match self {
+            DistortionShape::SoftClip => DistortionShape::HardClip,
+            DistortionShape::HardClip => DistortionShape::Foldback,
+            DistortionShape::Foldback => DistortionShape::SoftClip
+        }
+    }
+}
+
+/// Implements the [Display] trait for [DistortionShape]
+impl fmt::Display for DistortionShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DistortionShape::SoftClip => write!(f, "SFT"),
+            DistortionShape::HardClip => write!(f, "HRD"),
+            DistortionShape::Foldback => write!(f, "FLD")
+        }
+    }
+}
+
+/// Settings of the stereo delay
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DelayParameters {
+    pub enabled: bool,
+    /// Balance between the dry signal at 0.0 and the echoes alone at 1.0.
+    pub mix: f32,
+    /// Delay time in seconds while the delay is not synced to the tempo.
+    pub time: f32,
+    /// Index into [LFO_DIVISIONS] of the note length of the delay time while it is synced to the tempo, if it is.
+    pub division: Option<usize>,
+    /// Share of every echo fed back into the delay, in the range [0.0, [MAX_DELAY_FEEDBACK]].
+    pub feedback: f32
+}
+
+impl DelayParameters {
+    /// Returns the delay time in seconds, which for a tempo-synced delay follows from the tempo in beats per minute.
+    pub fn seconds(&self, tempo: f32) -> f32 {
+        match self.division {
+            Some(division) => (LFO_DIVISIONS[division].1 * 60.0 / tempo).min(MAX_DELAY_TIME),
+            None => self.time
+        }
+    }
+}
+
+/// Settings of the algorithmic reverb
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReverbParameters {
+    pub enabled: bool,
+    /// Balance between the dry signal at 0.0 and the reverberation alone at 1.0.
+    pub mix: f32,
+    /// Size of the simulated room in the range [0.0, 1.0], where larger rooms reverberate longer.
+    pub size: f32,
+    /// Damping of high frequencies in the range [0.0, 1.0], where more damping gives a darker reverberation.
+    pub damping: f32
+}
+
+/// Settings of the chorus, which turns into a flanger with short delays and feedback
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChorusParameters {
+    pub enabled: bool,
+    /// Balance between the dry signal at 0.0 and the modulated signal alone at 1.0.
+    pub mix: f32,
+    /// Rate in Hz at which the delay is swept.
+    pub rate: f32,
+    /// Depth of the sweep in the range [0.0, 1.0].
+    pub depth: f32,
+    /// Whether the effect flanges rather than choruses.
+    pub flanger: bool
+}
+
+/// Settings of the waveshaping distortion
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DistortionParameters {
+    pub enabled: bool,
+    /// Balance between the dry signal at 0.0 and the distorted signal alone at 1.0.
+    pub mix: f32,
+    /// Gain in decibels applied ahead of the shaping curve, in the range [0.0, [MAX_DISTORTION_DRIVE]].
+    pub drive: f32,
+    pub shape: DistortionShape
+}
+
+/// Settings of the bitcrusher
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BitcrusherParameters {
+    pub enabled: bool,
+    /// Balance between the dry signal at 0.0 and the crushed signal alone at 1.0.
+    pub mix: f32,
+    /// Bit depth the signal is quantized to, in the range [[MIN_CRUSHER_BITS], [MAX_CRUSHER_BITS]].
+    pub bits: u32,
+    /// Factor the sample rate is divided by, where 1 keeps the sample rate.
+    pub downsample: u32
+}
+
+/// Enumerates the signals the compressor reacts to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SidechainMode {
+    /// The compressor follows the level of the signal it compresses.
+    Off,
+    /// Every started note ducks the signal, as if keyed by a kick drum played along with it.
+    Notes,
+    /// Every beat at the tempo ducks the signal, like a metronome keying the compressor.
+    Tempo
+}
+
+impl SidechainMode {
+    /// Returns the next sidechain mode in the order off, notes and tempo.
+    pub fn next(&self) -> SidechainMode {
+        match self {
+            SidechainMode::Off => SidechainMode::Notes,
+            SidechainMode::Notes => SidechainMode::Tempo,
+            SidechainMode::Tempo => SidechainMode::Off
+        }
+    }
+}
+
+/// Settings of the compressor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompressorParameters {
+    pub enabled: bool,
+    /// Balance between the dry signal at 0.0 and the compressed signal alone at 1.0, which allows parallel compression.
+    pub mix: f32,
+    /// Level in decibels above which the signal is compressed, in the range [[MIN_COMPRESSOR_THRESHOLD], 0.0].
+    pub threshold: f32,
+    /// Factor by which the level above the threshold is reduced, in the range [1.0, [MAX_COMPRESSOR_RATIO]].
+    pub ratio: f32,
+    /// Time in seconds the compressor takes to reduce the gain once the level rises above the threshold.
+    pub attack: f32,
+    /// Time in seconds the compressor takes to restore the gain once the level falls below the threshold.
+    pub release: f32,
+    /// Gain in decibels applied after compression, in the range [0.0, [MAX_COMPRESSOR_MAKEUP]].
+    pub makeup: f32,
+    pub sidechain: SidechainMode
+}
+
+/// Settings of a single band of the equalizer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EqBand {
+    /// Center frequency of a peaking band, or corner frequency of a shelf, in Hz.
+    pub frequency: f32,
+    /// Boost or cut in decibels, in the range [-[MAX_EQ_GAIN], [MAX_EQ_GAIN]].
+    pub gain: f32,
+    /// Quality factor, which sets the width of a peaking band and the steepness of a shelf.
+    pub q: f32
+}
+
+/// Settings of the parametric equalizer on the master output
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EqParameters {
+    pub enabled: bool,
+    pub bands: [EqBand; EQ_BANDS]
+}
+
+impl Default for EqParameters {
+    fn default() -> Self {
+        EqParameters {
+            enabled: false,
+            bands: [
+                EqBand { frequency: 100.0, gain: 0.0, q: 0.71 },
+                EqBand { frequency: 500.0, gain: 0.0, q: 1.0 },
+                EqBand { frequency: 2000.0, gain: 0.0, q: 1.0 },
+                EqBand { frequency: 8000.0, gain: 0.0, q: 0.71 }
+            ]
+        }
+    }
+}
+
+/// Settings of the master effects chain, which processes the sum of all voices in the given order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EffectsParameters {
+    pub order: [ChainEffect; EFFECT_COUNT],
+    pub delay: DelayParameters,
+    pub reverb: ReverbParameters,
+    pub chorus: ChorusParameters,
+    pub distortion: DistortionParameters,
+    pub bitcrusher: BitcrusherParameters,
+    pub compressor: CompressorParameters
+}
+
+impl EffectsParameters {
+    /// Returns true if the given effect processes the signal, i.e. is not bypassed.
+    pub fn is_enabled(&self, kind: EffectKind) -> bool {
+        match kind {
+            EffectKind::Delay => self.delay.enabled,
+            EffectKind::Reverb => self.reverb.enabled,
+            EffectKind::Chorus => self.chorus.enabled,
+            EffectKind::Distortion => self.distortion.enabled,
+            EffectKind::Bitcrusher => self.bitcrusher.enabled,
+            EffectKind::Compressor => self.compressor.enabled
+        }
+    }
+
+    /// Bypasses the given effect if it is enabled and enables it otherwise.
+    pub fn toggle(&mut self, kind: EffectKind) {
+        let enabled = match kind {
+            EffectKind::Delay => &mut self.delay.enabled,
+            EffectKind::Reverb => &mut self.reverb.enabled,
+            EffectKind::Chorus => &mut self.chorus.enabled,
+            EffectKind::Distortion => &mut self.distortion.enabled,
+            EffectKind::Bitcrusher => &mut self.bitcrusher.enabled,
+            EffectKind::Compressor => &mut self.compressor.enabled
+        };
+        *enabled = !*enabled;
+    }
+
+    /// Returns a mutable reference to the wet/dry balance of the given effect.
+    pub fn mix_mut(&mut self, kind: EffectKind) -> &mut f32 {
+        match kind {
+            EffectKind::Delay => &mut self.delay.mix,
+            EffectKind::Reverb => &mut self.reverb.mix,
+            EffectKind::Chorus => &mut self.chorus.mix,
+            EffectKind::Distortion => &mut self.distortion.mix,
+            EffectKind::Bitcrusher => &mut self.bitcrusher.mix,
+            EffectKind::Compressor => &mut self.compressor.mix
+        }
+    }
+
+    /// Moves the given effect one position later in the chain, moving the last effect to the front.
+    pub fn move_later(&mut self, effect: ChainEffect) {
+        if let Some(position) = self.order.iter().position(|e| *e == effect) {
+            if position + 1 < EFFECT_COUNT {
+                self.order.swap(position, position + 1);
+            } else {
+                self.order.rotate_right(1);
+            }
+        }
+    }
+}
+
+impl Default for EffectsParameters {
+    fn default() -> Self {
+        EffectsParameters {
+            order: [ChainEffect::Distortion, ChainEffect::Bitcrusher, ChainEffect::Chorus, ChainEffect::Delay, ChainEffect::Reverb],
+            delay: DelayParameters { enabled: false, mix: 0.3, time: 0.3, division: None, feedback: 0.35 },
+            reverb: ReverbParameters { enabled: false, mix: 0.25, size: 0.5, damping: 0.5 },
+            chorus: ChorusParameters { enabled: false, mix: 0.5, rate: 0.8, depth: 0.5, flanger: false },
+            distortion: DistortionParameters { enabled: false, mix: 1.0, drive: 12.0, shape: DistortionShape::SoftClip },
+            bitcrusher: BitcrusherParameters { enabled: false, mix: 1.0, bits: 8, downsample: 4 },
+            compressor: CompressorParameters {
+                enabled: false,
+                mix: 1.0,
+                threshold: -18.0,
+                ratio: 4.0,
+                attack: 0.01,
+                release: 0.2,
+                makeup: 6.0,
+                sidechain: SidechainMode::Off
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::effects::{ReverbParameters, StereoEffect};
+use crate::waveforms::SAMPLE_RATE;
+
+/// Sample rate the delay lengths of the original Freeverb were tuned for
+const TUNING_SAMPLE_RATE: f32 = 44100.0;
+
+/// Lengths of the parallel comb filters in samples at the tuning sample rate
+const COMB_LENGTHS: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+
+/// Lengths of the serial all-pass filters in samples at the tuning sample rate
+const ALLPASS_LENGTHS: [usize; 4] = [556, 441, 341, 225];
+
+/// Number of samples the filters of the right channel are longer than those of the left one, decorrelating the channels
+const STEREO_SPREAD: usize = 23;
+
+/// Gain applied to the input, as the comb filters add up to a much louder signal
+const INPUT_GAIN: f32 = 0.015;
+
+/// Feedback of the comb filters for the smallest room, and the range added for larger rooms
+const ROOM_OFFSET: f32 = 0.7;
+const ROOM_SCALE: f32 = 0.28;
+
+/// Damping of the comb filters at full damping
+const DAMPING_SCALE: f32 = 0.4;
+
+/// Feedback of the all-pass filters
+const ALLPASS_FEEDBACK: f32 = 0.5;
+
+/// Returns a length tuned for [TUNING_SAMPLE_RATE] scaled to the sample rate of the synthesizer.
+fn scaled_length(length: usize) -> usize {
+    (length as f32 * SAMPLE_RATE / TUNING_SAMPLE_RATE) as usize
+}
+
+/// Feedback comb filter with a low-pass filter in its feedback path, which makes high frequencies die away faster
+#[derive(Debug)]
+struct Comb {
+    buffer: Vec<f32>,
+    index: usize,
+    filter_store: f32
+}
+
+impl Comb {
+    fn new(length: usize) -> Self {
+        Comb { buffer: vec![0.0; length], index: 0, filter_store: 0.0 }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let output = self.buffer[self.index];
+        self.filter_store = output * (1.0 - damping) + self.filter_store * damping;
+        self.buffer[self.index] = input + self.filter_store * feedback;
+        self.index = (self.index + 1) % self.buffer.len();
+
+        output
+    }
+}
+
+/// All-pass filter diffusing the echoes of the comb filters without colouring their spectrum
+#[derive(Debug)]
+struct Allpass {
+    buffer: Vec<f32>,
+    index: usize
+}
+
+impl Allpass {
+    fn new(length: usize) -> Self {
+        Allpass { buffer: vec![0.0; length], index: 0 }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let buffered = self.buffer[self.index];
+        self.buffer[self.index] = input + buffered * ALLPASS_FEEDBACK;
+        self.index = (self.index + 1) % self.buffer.len();
+
+        buffered - input
+    }
+}
+
+/// The comb and all-pass filters reverberating a single channel
+#[derive(Debug)]
+struct ReverbChannel {
+    combs: Vec<Comb>,
+    allpasses: Vec<Allpass>
+}
+
+impl ReverbChannel {
+    fn new(spread: usize) -> Self {
+        ReverbChannel {
+            combs: COMB_LENGTHS.iter().map(|length| Comb::new(scaled_length(length + spread))).collect(),
+            allpasses: ALLPASS_LENGTHS.iter().map(|length| Allpass::new(scaled_length(length + spread))).collect()
+        }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let combed = self.combs.iter_mut().map(|comb| comb.process(input, feedback, damping)).sum();
+        self.allpasses.iter_mut().fold(combed, |sample, allpass| allpass.process(sample))
+    }
+}
+
+/// Algorithmic reverb after Jezar's Freeverb, feeding the mono sum of the input through eight parallel comb filters
+/// followed by four serial all-pass filters per channel
+#[derive(Debug)]
+pub struct Reverb {
+    channels: [ReverbChannel; 2],
+    feedback: f32,
+    damping: f32,
+    mix: f32
+}
+
+impl Reverb {
+    pub fn new() -> Self {
+        Reverb { channels: [ReverbChannel::new(0), ReverbChannel::new(STEREO_SPREAD)], feedback: ROOM_OFFSET, damping: 0.0, mix: 0.0 }
+    }
+
+    /// Applies the room size, damping and mix from the next processed frame onwards.
+    pub fn set_parameters(&mut self, parameters: &ReverbParameters) {
+        self.feedback = ROOM_OFFSET + parameters.size.clamp(0.0, 1.0) * ROOM_SCALE;
+        self.damping = parameters.damping.clamp(0.0, 1.0) * DAMPING_SCALE;
+        self.mix = parameters.mix.clamp(0.0, 1.0);
+    }
+}
+
+/// Implementation of the [StereoEffect] trait for the [Reverb]
+impl StereoEffect for Reverb {
+    fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let input = (left + right) * INPUT_GAIN;
+        let wet_left = self.channels[0].process(input, self.feedback, self.damping);
+        let wet_right = self.channels[1].process(input, self.feedback, self.damping);
+
+        (left * (1.0 - self.mix) + wet_left * self.mix, right * (1.0 - self.mix) + wet_right * self.mix)
+    }
+}
@@ -0,0 +1,31 @@
+use crate::effects::{DEFAULT_STEREO_WIDTH, MAX_STEREO_WIDTH, StereoEffect};
+
+/// Narrows or widens the stereo field by scaling the side signal, i.e. the difference between the channels.
+///
+/// The mid signal, i.e. the sum of the channels, is left untouched, so the width may be changed without
+/// affecting sounds in the center. A width of 0.0 collapses the field to mono.
+#[derive(Debug)]
+pub struct StereoWidth {
+    width: f32
+}
+
+impl StereoWidth {
+    pub fn new() -> Self {
+        StereoWidth { width: DEFAULT_STEREO_WIDTH }
+    }
+
+    /// Sets the width in the range [0.0, 2.0], where 1.0 leaves the signal unchanged.
+    pub fn set_width(&mut self, width: f32) {
+        self.width = width.clamp(0.0, MAX_STEREO_WIDTH);
+    }
+}
+
+/// Implementation of the [StereoEffect] trait for the [StereoWidth]
+impl StereoEffect for StereoWidth {
+    fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let mid = 0.5 * (left + right);
+        let side = 0.5 * (left - right) * self.width;
+
+        (mid + side, mid - side)
+    }
+}
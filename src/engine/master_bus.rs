@@ -0,0 +1,76 @@
+use crate::effects::{ChainEffect, EffectsParameters, StereoEffect};
+use crate::effects::bitcrusher::Bitcrusher;
+use crate::effects::chorus::Chorus;
+use crate::effects::delay::Delay;
+use crate::effects::distortion::Distortion;
+use crate::effects::reverb::Reverb;
+use crate::effects::stereo_width::StereoWidth;
+use crate::engine::StereoParameters;
+use crate::waveforms::STEREO;
+
+/// The stereo bus all voices are summed onto, passing the sum through the master effects chain and the stereo width
+/// before it reaches the compressor, equalizer and limiter of the output
+pub struct MasterBus {
+    effects: EffectsParameters,
+    delay: Delay,
+    reverb: Reverb,
+    chorus: Chorus,
+    distortion: Distortion,
+    bitcrusher: Bitcrusher,
+    width: StereoWidth
+}
+
+impl MasterBus {
+    pub fn new() -> Self {
+        MasterBus {
+            effects: EffectsParameters::default(),
+            delay: Delay::new(),
+            reverb: Reverb::new(),
+            chorus: Chorus::new(),
+            distortion: Distortion::new(),
+            bitcrusher: Bitcrusher::new(),
+            width: StereoWidth::new()
+        }
+    }
+
+    /// Applies the stereo settings to the master effects from the next processed block onwards.
+    pub fn set_stereo(&mut self, stereo: &StereoParameters) {
+        self.width.set_width(stereo.width);
+    }
+
+    /// Applies the order, bypass and settings of the effects chain from the next processed block onwards,
+    /// where a tempo-synced delay follows the tempo in beats per minute.
+    pub fn set_effects(&mut self, effects: &EffectsParameters, tempo: f32) {
+        self.effects = *effects;
+        self.delay.set_parameters(&effects.delay, tempo);
+        self.reverb.set_parameters(&effects.reverb);
+        self.chorus.set_parameters(&effects.chorus);
+        self.distortion.set_parameters(&effects.distortion);
+        self.bitcrusher.set_parameters(&effects.bitcrusher);
+    }
+
+    /// Processes a block of interleaved left and right samples in place, passing them through the enabled effects
+    /// of the chain in order before applying the stereo width.
+    pub fn process(&mut self, buffer: &mut [f32]) {
+        for frame in buffer.chunks_exact_mut(STEREO as usize) {
+            let (mut left, mut right) = (frame[0], frame[1]);
+
+            for effect in self.effects.order {
+                if !self.effects.is_enabled(effect.into()) {
+                    continue;
+                }
+
+                let effect: &mut dyn StereoEffect = match effect {
+                    ChainEffect::Delay => &mut self.delay,
+                    ChainEffect::Reverb => &mut self.reverb,
+                    ChainEffect::Chorus => &mut self.chorus,
+                    ChainEffect::Distortion => &mut self.distortion,
+                    ChainEffect::Bitcrusher => &mut self.bitcrusher
+                };
+                (left, right) = effect.process(left, right);
+            }
+
+            (frame[0], frame[1]) = self.width.process(left, right);
+        }
+    }
+}
@@ -0,0 +1,374 @@
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rodio::Source;
+
+use crate::effects::{EffectsParameters, EqParameters, SidechainMode};
+use crate::effects::compressor::Compressed;
+use crate::effects::equalizer::Equalized;
+use crate::effects::limiter::Limited;
+use crate::engine::{BLOCK_SIZE, PITCH_BEND_RATE, StereoParameters, UnisonParameters};
+use crate::engine::master_bus::MasterBus;
+use crate::engine::voice::{Voice, VoiceId, VoiceParameters};
+use crate::envelopes::adsr::AdsrParameters;
+use crate::envelopes::EnvelopeStage;
+use crate::filters::{FilterMode, FilterParameters, MAX_CUTOFF};
+use crate::modulation::{LFO_COUNT, ModulationOffsets, ModulationParameters, ModulationSources};
+use crate::modulation::lfo::Lfo;
+use crate::waveforms::{SAMPLE_RATE, SecondOscillatorParameters, STEREO, SubNoiseParameters};
+use crate::waveforms::fm::FmParameters;
+
+/// Owns the active voices and sums them onto the stereo master bus
+pub struct Mixer {
+    voices: Vec<Voice>,
+    master: MasterBus,
+    max_voices: usize,
+    allocations: u64,
+    filter: FilterParameters,
+    second_oscillator: SecondOscillatorParameters,
+    sub_noise: SubNoiseParameters,
+    unison: UnisonParameters,
+    modulation: ModulationParameters,
+    effects: EffectsParameters,
+    equalizer: EqParameters,
+    wavetable_position: f32,
+    fm: FmParameters,
+    lfos: [Lfo; LFO_COUNT],
+    beat_phase: f32,
+    sidechain_keyed: bool,
+    gain_reduction: f32,
+    modulated_cutoff: f32,
+    pitch_bend: f32,
+    pitch_bend_target: f32
+}
+
+impl Mixer {
+    pub fn new(max_voices: usize) -> Self {
+        Mixer {
+            voices: Vec::with_capacity(max_voices),
+            master: MasterBus::new(),
+            max_voices,
+            allocations: 0,
+            filter: FilterParameters {
+                mode: FilterMode::Off,
+                cutoff: MAX_CUTOFF,
+                resonance: 0.0,
+                envelope: AdsrParameters::default(),
+                envelope_amount: 0.0
+            },
+            second_oscillator: SecondOscillatorParameters::default(),
+            sub_noise: SubNoiseParameters::default(),
+            unison: UnisonParameters::default(),
+            modulation: ModulationParameters::default(),
+            effects: EffectsParameters::default(),
+            equalizer: EqParameters::default(),
+            wavetable_position: 0.0,
+            fm: FmParameters::default(),
+            lfos: std::array::from_fn(|index| Lfo::new(0x2545_F491 + index as u32)),
+            beat_phase: 0.0,
+            sidechain_keyed: false,
+            gain_reduction: 0.0,
+            modulated_cutoff: MAX_CUTOFF,
+            pitch_bend: 0.0,
+            pitch_bend_target: 0.0
+        }
+    }
+
+    /// Adds a voice to the mixer and returns the identifier assigned to it.
+    ///
+    /// If every voice slot is occupied, a voice is stolen and replaced by the new one. Voices which have
+    /// already been released are stolen first, the oldest voice otherwise. Every new voice keys the compressor
+    /// if it is enabled and its sidechain listens to the notes.
+    pub fn allocate(&mut self, mut voice: Voice) -> VoiceId {
+        self.allocations += 1;
+        self.key_sidechain(SidechainMode::Notes);
+        voice.set_id(self.allocations);
+
+        if self.voices.len() < self.max_voices {
+            self.voices.push(voice);
+        } else if let Some(oldest) = self.voices.iter_mut().min_by_key(|v| (!v.is_released(), v.id())) {
+            *oldest = voice;
+        }
+
+        self.allocations
+    }
+
+    /// Releases the voice with the given identifier. Voices which have already been stolen are ignored.
+    pub fn release(&mut self, id: VoiceId) {
+        if let Some(voice) = self.voices.iter_mut().find(|v| v.id() == id) {
+            voice.release();
+        }
+    }
+
+    /// Slides the voice with the given identifier to a new frequency and returns whether it was still sounding.
+    /// Voices which have already been stolen or have finished are ignored.
+    pub fn glide(&mut self, id: VoiceId, frequency: f32, time: f32) -> bool {
+        match self.voices.iter_mut().find(|v| v.id() == id && !v.is_finished()) {
+            Some(voice) => {
+                voice.glide_to(frequency, time);
+                true
+            }
+            None => false
+        }
+    }
+
+    /// Returns the envelope stage of the most recently started voice, or idle if no voice is sounding.
+    pub fn latest_envelope_stage(&self) -> EnvelopeStage {
+        self.voices.iter()
+            .max_by_key(|v| v.id())
+            .map_or(EnvelopeStage::Idle, |v| v.envelope_stage())
+    }
+
+    /// Sets the filter settings applied to every voice from the next rendered block onwards.
+    pub fn set_filter(&mut self, filter: FilterParameters) {
+        self.filter = filter;
+    }
+
+    /// Sets the detune, mix and sync of the second oscillator of every voice from the next rendered block onwards.
+    pub fn set_second_oscillator(&mut self, second_oscillator: SecondOscillatorParameters) {
+        self.second_oscillator = second_oscillator;
+    }
+
+    /// Sets the levels of the sub-oscillator and noise layer of every voice from the next rendered block onwards.
+    pub fn set_sub_noise(&mut self, sub_noise: SubNoiseParameters) {
+        self.sub_noise = sub_noise;
+    }
+
+    /// Sets the unison detune and stereo spread of every voice from the next rendered block onwards.
+    pub fn set_unison(&mut self, unison: UnisonParameters) {
+        self.unison = unison;
+    }
+
+    /// Sets the read position within the wavetables of every voice from the next rendered block onwards.
+    pub fn set_wavetable_position(&mut self, position: f32) {
+        self.wavetable_position = position;
+    }
+
+    /// Sets the algorithm and operators of the FM oscillators of every voice from the next rendered block onwards.
+    pub fn set_fm(&mut self, fm: FmParameters) {
+        self.fm = fm;
+    }
+
+    /// Sets the stereo width of the master bus from the next rendered block onwards.
+    pub fn set_stereo(&mut self, stereo: StereoParameters) {
+        self.master.set_stereo(&stereo);
+    }
+
+    /// Sets the LFO settings, the routes of the modulation matrix and the mod wheel from the next rendered block onwards.
+    pub fn set_modulation(&mut self, modulation: ModulationParameters) {
+        self.modulation = modulation;
+    }
+
+    /// Sets the order, bypass and settings of the master effects chain from the next rendered block onwards.
+    pub fn set_effects(&mut self, effects: EffectsParameters) {
+        self.effects = effects;
+    }
+
+    /// Sets the bypass and the bands of the equalizer on the master output from the next update of the output onwards.
+    pub fn set_equalizer(&mut self, equalizer: EqParameters) {
+        self.equalizer = equalizer;
+    }
+
+    /// Returns the filter cutoff in Hz as modulated during the most recently rendered block.
+    pub fn modulated_cutoff(&self) -> f32 {
+        self.modulated_cutoff
+    }
+
+    /// Returns the gain reduction in decibels the limiter of the master output applied as of its most recent update.
+    pub fn gain_reduction(&self) -> f32 {
+        self.gain_reduction
+    }
+
+    /// Remembers to key the compressor on the next update of the output if its sidechain listens to the given source.
+    fn key_sidechain(&mut self, source: SidechainMode) {
+        self.sidechain_keyed |= source == self.effects.compressor.sidechain;
+    }
+
+    /// Hands the settings of the compressor and the equalizer to the master output, keys the compressor if a note
+    /// or beat has done so since the previous update and picks up the gain reduction of the limiter.
+    pub fn update_output(&mut self, output: &mut MasterOutput) {
+        self.gain_reduction = output.effect().gain_reduction();
+
+        let equalized = output.inner_mut();
+        equalized.effect_mut().set_parameters(&self.equalizer);
+
+        let compressor = equalized.inner_mut().effect_mut();
+        compressor.set_parameters(&self.effects.compressor);
+        if mem::take(&mut self.sidechain_keyed) {
+            compressor.trigger(self.effects.compressor.sidechain);
+        }
+    }
+
+    /// Sets the pitch bend in semitones which all voices glide towards.
+    pub fn set_pitch_bend(&mut self, semitones: f32) {
+        self.pitch_bend_target = semitones;
+    }
+
+    /// Fills the buffer with the interleaved stereo sum of all voices, passed through the master bus,
+    /// and releases the voices which have finished.
+    pub fn render(&mut self, buffer: &mut [f32]) {
+        buffer.fill(0.0);
+
+        // Glide the pitch bend towards its target at a limited rate, so bending sweeps rather than jumps
+        let frames = buffer.len() / STEREO as usize;
+        let max_step = PITCH_BEND_RATE * frames as f32 / SAMPLE_RATE;
+        self.pitch_bend += (self.pitch_bend_target - self.pitch_bend).clamp(-max_step, max_step);
+
+        let pitch_ratio = 2.0_f32.powf(self.pitch_bend / 12.0);
+
+        // Advance the LFOs by the duration of the block. The envelope and velocity are filled in by every voice
+        let mut sources = ModulationSources { lfos: [0.0; LFO_COUNT], envelope: 0.0, velocity: 0.0, mod_wheel: self.modulation.mod_wheel };
+        for ((lfo, parameters), value) in self.lfos.iter_mut().zip(self.modulation.lfos.iter()).zip(sources.lfos.iter_mut()) {
+            *value = lfo.advance(parameters, self.modulation.tempo, frames as f32 / SAMPLE_RATE);
+        }
+
+        let parameters = VoiceParameters {
+            filter: self.filter,
+            pitch_ratio,
+            second: self.second_oscillator,
+            sub_noise: self.sub_noise,
+            unison: self.unison,
+            slots: self.modulation.slots,
+            sources,
+            wavetable_position: self.wavetable_position,
+            fm: self.fm
+        };
+
+        for voice in self.voices.iter_mut() {
+            voice.render(buffer, &parameters);
+        }
+
+        // Report the cutoff of the most recent voice, or the cutoff a silent voice would be modulated to if none is sounding
+        self.modulated_cutoff = match self.voices.iter().max_by_key(|v| v.id()) {
+            Some(voice) => voice.modulated_cutoff(),
+            None => self.filter.cutoff * 2.0_f32.powf(ModulationOffsets::evaluate(&self.modulation.slots, &sources).cutoff)
+        };
+
+        self.voices.retain(|voice| !voice.is_finished());
+
+        // The delay may be synced to the same tempo as the LFOs, which also keys the compressor on every beat
+        self.master.set_effects(&self.effects, self.modulation.tempo);
+
+        self.beat_phase += self.modulation.tempo / 60.0 * frames as f32 / SAMPLE_RATE;
+        if self.beat_phase >= 1.0 {
+            self.beat_phase = self.beat_phase.fract();
+            self.key_sidechain(SidechainMode::Tempo);
+        }
+
+        self.master.process(buffer);
+    }
+}
+
+/// The stages following the master bus on the way to the output stream, which process the mixed blocks as [Source]
+/// adapters: the compressor, the equalizer and finally the limiter, so that the output never clips
+pub type MasterOutput = Limited<Equalized<Compressed<MixerSource>>>;
+
+/// Number of interleaved samples in a block of stereo frames
+const BLOCK_SAMPLES: usize = BLOCK_SIZE * STEREO as usize;
+
+/// [Source] pulling blocks of mixed stereo frames from a shared [Mixer]
+pub struct MixerSource {
+    mixer: Arc<Mutex<Mixer>>,
+    buffer: [f32; BLOCK_SAMPLES],
+    position: usize
+}
+
+impl MixerSource {
+    pub fn new(mixer: Arc<Mutex<Mixer>>) -> Self {
+        MixerSource { mixer, buffer: [0.0; BLOCK_SAMPLES], position: BLOCK_SAMPLES }
+    }
+}
+
+/// Implementation of the [Iterator] trait for the [MixerSource]
+impl Iterator for MixerSource {
+    type Item = f32;
+    fn next(&mut self) -> Option<f32> {
+        // Render a new block once the previous one has been consumed
+        if self.position == BLOCK_SAMPLES {
+            self.mixer.lock().unwrap().render(&mut self.buffer);
+            self.position = 0;
+        }
+
+        let sample = self.buffer[self.position];
+        self.position += 1;
+
+        Some(sample)
+    }
+}
+
+/// Implementation of the [Source] trait for the [MixerSource]
+impl Source for MixerSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        STEREO
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE as u32
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::MAX_VOICES;
+    use crate::waveforms::sine_wave::SineWave;
+
+    use super::*;
+
+    /// Returns a voice playing sine waves at 440 Hz.
+    fn voice() -> Voice {
+        let layers = vec![(Box::new(SineWave::new(440.0)) as _, Box::new(SineWave::new(440.0)) as _)];
+        let sub_noise = (Box::new(SineWave::new(220.0)) as _, Box::new(SineWave::new(440.0)) as _);
+        Voice::new(layers, sub_noise, 440.0, AdsrParameters::default(), 0.0, 1.0)
+    }
+
+    /// Returns the identifiers of the voices held by the mixer in ascending order.
+    fn voice_ids(mixer: &Mixer) -> Vec<VoiceId> {
+        let mut ids: Vec<_> = mixer.voices.iter().map(|v| v.id()).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn steals_oldest_voice_once_all_voices_are_held() {
+        let mut mixer = Mixer::new(MAX_VOICES);
+        for _ in 0..MAX_VOICES {
+            mixer.allocate(voice());
+        }
+        assert_eq!(voice_ids(&mixer), (1..=MAX_VOICES as VoiceId).collect::<Vec<_>>());
+
+        let id = mixer.allocate(voice());
+
+        assert_eq!(id, MAX_VOICES as VoiceId + 1);
+        assert_eq!(voice_ids(&mixer), (2..=id).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn steals_oldest_released_voice_before_held_voices() {
+        let mut mixer = Mixer::new(MAX_VOICES);
+        for _ in 0..MAX_VOICES {
+            mixer.allocate(voice());
+        }
+        mixer.release(6);
+        mixer.release(4);
+
+        mixer.allocate(voice());
+        assert!(!voice_ids(&mixer).contains(&4) && voice_ids(&mixer).contains(&6));
+
+        mixer.allocate(voice());
+        assert!(!voice_ids(&mixer).contains(&6) && voice_ids(&mixer).contains(&1));
+
+        // With no released voice left, the oldest held voice is stolen again
+        mixer.allocate(voice());
+        assert!(!voice_ids(&mixer).contains(&1));
+        assert_eq!(mixer.voices.len(), MAX_VOICES);
+    }
+}
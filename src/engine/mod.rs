@@ -0,0 +1,195 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rodio::Source;
+
+use crate::effects::compressor::Compressor;
+use crate::effects::equalizer::Equalizer;
+use crate::effects::limiter::Limiter;
+use crate::effects::Processed;
+use crate::engine::mixer::{Mixer, MixerSource};
+use crate::engine::voice::{LayerSources, SubNoiseSources, Voice, VoiceId};
+use crate::envelopes::adsr::AdsrParameters;
+use crate::effects::{DEFAULT_STEREO_WIDTH, EffectsParameters, EqParameters};
+use crate::envelopes::EnvelopeStage;
+use crate::filters::FilterParameters;
+use crate::modulation::ModulationParameters;
+use crate::waveforms::{SAMPLE_RATE, SecondOscillatorParameters, SubNoiseParameters};
+use crate::waveforms::fm::FmParameters;
+
+pub mod master_bus;
+pub mod mixer;
+pub mod voice;
+
+/// Maximum number of voices that may sound simultaneously
+pub const MAX_VOICES: usize = 8;
+
+/// Number of stereo frames rendered each time the audio thread locks the mixer
+pub const BLOCK_SIZE: usize = 64;
+
+/// Maximum pitch bend in semitones, in either direction
+pub const PITCH_BEND_RANGE: f32 = 2.0;
+
+/// Rate in semitones per second at which the pitch bend moves towards its target
+pub const PITCH_BEND_RATE: f32 = 24.0;
+
+/// Shortest time a voice may take to glide to a new note in legato mode, in seconds
+pub const MIN_GLIDE_TIME: f32 = 0.01;
+
+/// Longest time a voice may take to glide to a new note in legato mode, in seconds
+pub const MAX_GLIDE_TIME: f32 = 2.0;
+
+/// Maximum number of detuned layers stacked by a single voice in unison mode
+pub const MAX_UNISON_VOICES: usize = 7;
+
+/// Maximum detune in cents between the center and the outermost layers of a unison stack
+pub const MAX_UNISON_DETUNE: f32 = 100.0;
+
+/// Settings of the unison stack of every voice
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnisonParameters {
+    /// Number of layers stacked by every voice started from now on, where 1 disables unison.
+    pub voices: usize,
+    /// Detune in cents between the center and the outermost layers.
+    pub detune: f32,
+    /// Width of the stereo field the layers are spread across, from 0.0 (mono) to 1.0 (hard left and right).
+    pub spread: f32
+}
+
+impl Default for UnisonParameters {
+    fn default() -> Self {
+        UnisonParameters { voices: 1, detune: 20.0, spread: 1.0 }
+    }
+}
+
+/// Placement of the voices within the stereo field
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StereoParameters {
+    /// Pan of every voice started from now on, from hard left at -1.0 over the center at 0.0 to hard right at 1.0.
+    pub pan: f32,
+    /// Width of the master bus, from mono at 0.0 over unchanged at 1.0 to extra wide at 2.0.
+    pub width: f32
+}
+
+impl Default for StereoParameters {
+    fn default() -> Self {
+        StereoParameters { pan: 0.0, width: DEFAULT_STEREO_WIDTH }
+    }
+}
+
+/// Handle to the polyphonic voice engine.
+///
+/// The handle is shared between the event loop, which starts voices, and the audio thread,
+/// which pulls mixed samples from the source returned by [Engine::source].
+#[derive(Clone)]
+pub struct Engine {
+    mixer: Arc<Mutex<Mixer>>
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Engine { mixer: Arc::new(Mutex::new(Mixer::new(MAX_VOICES))) }
+    }
+
+    /// Returns a [rodio::Source] which mixes all active voices and passes the mix through the master output,
+    /// to be handed to the output stream once.
+    ///
+    /// The compressor, equalizer and limiter of the output pick up their settings from the mixer once per block.
+    pub fn source(&self) -> impl Source<Item = f32> + Send {
+        let mixer = Arc::clone(&self.mixer);
+        let output = Processed::new(Processed::new(Processed::new(MixerSource::new(Arc::clone(&self.mixer)), Compressor::new()),
+                                                   Equalizer::new()), Limiter::new());
+
+        output.periodic_access(Duration::from_secs_f32(BLOCK_SIZE as f32 / SAMPLE_RATE),
+                               move |output| mixer.lock().unwrap().update_output(output))
+    }
+
+    /// Starts a new voice stacking the given layers of oscillators along with a sub-oscillator and noise generator
+    /// at a frequency in Hz shaped by an envelope, placed at a pan in the range [-1.0, 1.0] and struck with a velocity
+    /// in the range [0.0, 1.0], stealing a voice if all are busy.
+    /// The returned identifier is used to release the voice again through [Engine::note_off].
+    pub fn note_on(&self, layers: Vec<LayerSources>, sub_noise: SubNoiseSources, frequency: f32, envelope: AdsrParameters, pan: f32, velocity: f32) -> VoiceId {
+        self.mixer.lock().unwrap().allocate(Voice::new(layers, sub_noise, frequency, envelope, pan, velocity))
+    }
+
+    /// Slides the pitch of the voice with the given identifier to a new frequency in Hz over a time in seconds,
+    /// without restarting its envelopes. Returns false if the voice has already been stolen or has finished.
+    pub fn glide(&self, id: VoiceId, frequency: f32, time: f32) -> bool {
+        self.mixer.lock().unwrap().glide(id, frequency, time)
+    }
+
+    /// Moves the voice with the given identifier into the release stage of its envelope.
+    pub fn note_off(&self, id: VoiceId) {
+        self.mixer.lock().unwrap().release(id);
+    }
+
+    /// Applies the filter settings to all sounding and future voices.
+    pub fn set_filter(&self, filter: FilterParameters) {
+        self.mixer.lock().unwrap().set_filter(filter);
+    }
+
+    /// Applies the detune, mix and sync of the second oscillator to all sounding and future voices.
+    pub fn set_second_oscillator(&self, second_oscillator: SecondOscillatorParameters) {
+        self.mixer.lock().unwrap().set_second_oscillator(second_oscillator);
+    }
+
+    /// Applies the levels of the sub-oscillator and noise layer to all sounding and future voices.
+    pub fn set_sub_noise(&self, sub_noise: SubNoiseParameters) {
+        self.mixer.lock().unwrap().set_sub_noise(sub_noise);
+    }
+
+    /// Applies the unison detune and stereo spread to all sounding and future voices.
+    pub fn set_unison(&self, unison: UnisonParameters) {
+        self.mixer.lock().unwrap().set_unison(unison);
+    }
+
+    /// Applies the read position within the wavetables to all sounding and future voices.
+    pub fn set_wavetable_position(&self, position: f32) {
+        self.mixer.lock().unwrap().set_wavetable_position(position);
+    }
+
+    /// Applies the algorithm and the ratio, index and feedback of the FM operators to all sounding and future voices.
+    pub fn set_fm(&self, fm: FmParameters) {
+        self.mixer.lock().unwrap().set_fm(fm);
+    }
+
+    /// Applies the stereo width to the master bus.
+    pub fn set_stereo(&self, stereo: StereoParameters) {
+        self.mixer.lock().unwrap().set_stereo(stereo);
+    }
+
+    /// Applies the order, bypass and settings of the master effects chain and the compressor following it.
+    pub fn set_effects(&self, effects: EffectsParameters) {
+        self.mixer.lock().unwrap().set_effects(effects);
+    }
+
+    /// Applies the bypass and the bands of the equalizer to the master output.
+    pub fn set_equalizer(&self, equalizer: EqParameters) {
+        self.mixer.lock().unwrap().set_equalizer(equalizer);
+    }
+
+    /// Applies the settings of the LFOs, the tempo they may be synced to, the modulation matrix and the mod wheel.
+    pub fn set_modulation(&self, modulation: ModulationParameters) {
+        self.mixer.lock().unwrap().set_modulation(modulation);
+    }
+
+    /// Bends the pitch of all voices by the given number of semitones.
+    pub fn set_pitch_bend(&self, semitones: f32) {
+        self.mixer.lock().unwrap().set_pitch_bend(semitones);
+    }
+
+    /// Returns the envelope stage of the most recently started voice.
+    pub fn latest_envelope_stage(&self) -> EnvelopeStage {
+        self.mixer.lock().unwrap().latest_envelope_stage()
+    }
+
+    /// Returns the filter cutoff in Hz as modulated during the most recently rendered block.
+    pub fn modulated_cutoff(&self) -> f32 {
+        self.mixer.lock().unwrap().modulated_cutoff()
+    }
+
+    /// Returns the gain reduction in decibels the master limiter applied as of the most recently rendered block.
+    pub fn gain_reduction(&self) -> f32 {
+        self.mixer.lock().unwrap().gain_reduction()
+    }
+}
@@ -0,0 +1,262 @@
+use crate::engine::{MAX_UNISON_DETUNE, MIN_GLIDE_TIME, UnisonParameters};
+use crate::envelopes::adsr::{Adsr, AdsrParameters};
+use crate::envelopes::EnvelopeStage;
+use crate::filters::{FilterMode, FilterParameters, MAX_FILTER_ENVELOPE_OCTAVES, MAX_RESONANCE};
+use crate::filters::state_variable::StateVariableFilter;
+use crate::modulation::{MODULATION_SLOTS, ModulationOffsets, ModulationSlot, ModulationSources};
+use crate::waveforms::{AMPLITUDE, Oscillator, SAMPLE_RATE, SecondOscillatorParameters, STEREO, SubNoiseParameters};
+use crate::waveforms::fm::FmParameters;
+use crate::waveforms::phase_accumulator::PhaseAccumulator;
+use crate::waveforms::upmix::Upmix;
+
+/// Boxed oscillator rendered by a single voice
+pub type VoiceSource = Box<dyn Oscillator>;
+
+/// The first and second oscillator of a single unison layer of a voice
+pub type LayerSources = (VoiceSource, VoiceSource);
+
+/// The sub-oscillator and noise generator of a voice
+pub type SubNoiseSources = (VoiceSource, VoiceSource);
+
+/// Identifies a voice started by [crate::engine::Engine::note_on]. Identifiers increase monotonically,
+/// so a lower identifier belongs to an older voice.
+pub type VoiceId = u64;
+
+/// The settings shared by all voices for a rendered block
+#[derive(Debug, Clone, Copy)]
+pub struct VoiceParameters {
+    /// The filter settings, which are applied from the start of the block.
+    pub filter: FilterParameters,
+    /// The factor the frequency of the note is multiplied with, e.g. 2.0 to bend it up one octave.
+    pub pitch_ratio: f32,
+    /// The detune, mix and sync of the second oscillator.
+    pub second: SecondOscillatorParameters,
+    /// The tuning and levels of the sub-oscillator and noise generator.
+    pub sub_noise: SubNoiseParameters,
+    /// The detune and stereo spread of the layers.
+    pub unison: UnisonParameters,
+    /// The routes of the modulation matrix.
+    pub slots: [ModulationSlot; MODULATION_SLOTS],
+    /// The values of the modulation sources shared by all voices, i.e. the LFOs and the mod wheel.
+    pub sources: ModulationSources,
+    /// The read position within wavetables, in the range [0.0, 1.0].
+    pub wavetable_position: f32,
+    /// The algorithm and operators of FM oscillators.
+    pub fm: FmParameters
+}
+
+/// A copy of the oscillators of a voice, detuned and panned according to its position within the unison stack
+struct Layer {
+    source: VoiceSource,
+    second_source: VoiceSource,
+    sync_phase: PhaseAccumulator,
+    upmix: Upmix
+}
+
+impl Layer {
+    fn new((source, second_source): LayerSources, frequency: f32) -> Self {
+        Layer { source, second_source, sync_phase: PhaseAccumulator::new(frequency), upmix: Upmix::new(0.0) }
+    }
+
+    /// Returns the next sample of the combined oscillators, restarting the second oscillator if it is synced
+    /// and the first oscillator starts a new period.
+    fn next_sample(&mut self, second: &SecondOscillatorParameters) -> f32 {
+        // The period of the first oscillator restarts once the phase has wrapped around to below one increment
+        if self.sync_phase.advance() < self.sync_phase.increment() && second.sync {
+            self.second_source.reset_phase();
+        }
+
+        second.combiner.combine(self.source.next_sample(), self.second_source.next_sample(), second.mix)
+    }
+}
+
+/// A single sounding note owned by the [crate::engine::mixer::Mixer], mixing a stack of detuned layers of two oscillators
+/// into the stereo field around its pan along with a sub-oscillator and noise generator, passing them through a filter
+/// per channel swept by its own ADSR envelope and shaping them with an amplitude ADSR envelope
+pub struct Voice {
+    layers: Vec<Layer>,
+    sub: VoiceSource,
+    noise: VoiceSource,
+    upmix: Upmix,
+    frequency: f32,
+    glide_target: f32,
+    glide_rate: f32,
+    pan: f32,
+    velocity: f32,
+    modulated_cutoff: f32,
+    filters: [StateVariableFilter; 2],
+    envelope: Adsr,
+    filter_envelope: Adsr,
+    id: VoiceId
+}
+
+impl Voice {
+    pub fn new(layers: Vec<LayerSources>, (sub, noise): SubNoiseSources, frequency: f32, envelope: AdsrParameters, pan: f32, velocity: f32) -> Self {
+        Voice {
+            layers: layers.into_iter().map(|sources| Layer::new(sources, frequency)).collect(),
+            sub,
+            noise,
+            upmix: Upmix::new(pan),
+            frequency,
+            glide_target: frequency,
+            glide_rate: 0.0,
+            pan,
+            velocity,
+            modulated_cutoff: 0.0,
+            filters: Default::default(),
+            envelope: Adsr::new(envelope),
+            // The filter envelope takes its times from the filter settings of every rendered block
+            filter_envelope: Adsr::new(AdsrParameters::default()),
+            id: 0
+        }
+    }
+
+    /// Returns the identifier assigned to the voice when it was allocated.
+    pub fn id(&self) -> VoiceId {
+        self.id
+    }
+
+    /// Sets the identifier of the voice, which is also used to determine its age when stealing voices.
+    pub fn set_id(&mut self, id: VoiceId) {
+        self.id = id;
+    }
+
+    /// Returns the stage of the voice's amplitude envelope.
+    pub fn envelope_stage(&self) -> EnvelopeStage {
+        self.envelope.stage()
+    }
+
+    /// Returns the filter cutoff in Hz as modulated during the most recently rendered block.
+    pub fn modulated_cutoff(&self) -> f32 {
+        self.modulated_cutoff
+    }
+
+    /// Returns true once the key of the voice has been released.
+    pub fn is_released(&self) -> bool {
+        matches!(self.envelope.stage(), EnvelopeStage::Release | EnvelopeStage::Idle)
+    }
+
+    /// Returns true once the envelope has faded out.
+    pub fn is_finished(&self) -> bool {
+        self.envelope.is_finished()
+    }
+
+    /// Slides the pitch of the voice to a new frequency in Hz over a time in seconds, at a constant rate in octaves
+    /// so that the glide sounds even across the keyboard. The envelopes carry on undisturbed.
+    pub fn glide_to(&mut self, frequency: f32, time: f32) {
+        self.glide_target = frequency;
+        self.glide_rate = (frequency / self.frequency).log2().abs() / time.max(MIN_GLIDE_TIME);
+    }
+
+    /// Moves the envelopes into their release stage as the key of the voice has been released.
+    pub fn release(&mut self) {
+        self.envelope.release();
+        self.filter_envelope.release();
+    }
+
+    /// Renders a block of the voice and adds it to the interleaved stereo buffer.
+    ///
+    /// # Parameters
+    /// - `buffer`: The block of interleaved left and right samples the voice is mixed into.
+    /// - `parameters`: The settings shared by all voices for the block.
+    pub fn render(&mut self, buffer: &mut [f32], parameters: &VoiceParameters) {
+        // Glide towards the target frequency by the duration of the block, like the pitch bend
+        if self.frequency != self.glide_target {
+            let max_step = self.glide_rate * (buffer.len() / STEREO as usize) as f32 / SAMPLE_RATE;
+            let octaves = (self.glide_target / self.frequency).log2().clamp(-max_step, max_step);
+            self.frequency = if octaves.abs() < max_step { self.glide_target } else { self.frequency * 2.0_f32.powf(octaves) };
+        }
+
+        // Evaluate the modulation matrix with the envelope and velocity of this voice
+        let sources = ModulationSources { envelope: self.envelope.level(), velocity: self.velocity, ..parameters.sources };
+        let modulation = ModulationOffsets::evaluate(&parameters.slots, &sources);
+
+        // Sweep the cutoff by the filter envelope, which is updated once per block like the modulation
+        self.filter_envelope.set_parameters(parameters.filter.envelope);
+        let envelope_octaves = self.filter_envelope.level() * parameters.filter.envelope_amount * MAX_FILTER_ENVELOPE_OCTAVES;
+
+        let filter = FilterParameters {
+            cutoff: parameters.filter.cutoff * 2.0_f32.powf(modulation.cutoff + envelope_octaves),
+            resonance: (parameters.filter.resonance + modulation.resonance * MAX_RESONANCE).clamp(0.0, MAX_RESONANCE),
+            ..parameters.filter
+        };
+        let second = SecondOscillatorParameters {
+            mix: (parameters.second.mix + modulation.oscillator_mix).clamp(0.0, 1.0),
+            ..parameters.second
+        };
+        let sub_noise = SubNoiseParameters {
+            sub_level: (parameters.sub_noise.sub_level + modulation.sub_level).clamp(0.0, 1.0),
+            noise_level: (parameters.sub_noise.noise_level + modulation.noise_level).clamp(0.0, 1.0),
+            ..parameters.sub_noise
+        };
+        let unison = UnisonParameters {
+            detune: (parameters.unison.detune + modulation.unison_detune * MAX_UNISON_DETUNE).clamp(0.0, MAX_UNISON_DETUNE),
+            ..parameters.unison
+        };
+        let pan = self.pan + modulation.pan;
+        let wavetable_position = (parameters.wavetable_position + modulation.wavetable_position).clamp(0.0, 1.0);
+        self.modulated_cutoff = filter.cutoff;
+
+        let frequency = self.frequency * parameters.pitch_ratio * 2.0_f32.powf(modulation.pitch / 12.0);
+        let layer_count = self.layers.len();
+
+        self.sub.set_frequency(frequency * sub_noise.sub_frequency_ratio());
+
+        for (index, layer) in self.layers.iter_mut().enumerate() {
+            // Spread the layers evenly from -1.0 to 1.0, detuning them downwards and panning them left of the voice's pan below the center
+            let position = if layer_count > 1 { 2.0 * index as f32 / (layer_count - 1) as f32 - 1.0 } else { 0.0 };
+            let layer_frequency = frequency * 2.0_f32.powf(position * unison.detune / 1200.0);
+
+            layer.source.set_fm_parameters(&parameters.fm);
+            layer.second_source.set_fm_parameters(&parameters.fm);
+
+            layer.source.set_frequency(layer_frequency);
+            layer.second_source.set_frequency(layer_frequency * second.frequency_ratio());
+            layer.sync_phase.set_frequency(layer_frequency);
+
+            layer.source.set_pulse_width_offset(modulation.pulse_width);
+            layer.second_source.set_pulse_width_offset(modulation.pulse_width);
+            layer.source.set_wavetable_position(wavetable_position);
+            layer.second_source.set_wavetable_position(wavetable_position);
+
+            layer.upmix.set_pan(pan + position * unison.spread);
+        }
+
+        self.upmix.set_pan(pan);
+
+        if filter.mode != FilterMode::Off {
+            for channel_filter in self.filters.iter_mut() {
+                channel_filter.set_parameters(filter.cutoff, filter.resonance);
+            }
+        }
+
+        // Scale the stack so that its loudness stays about the same regardless of the number of layers
+        let stack_gain = 1.0 / (layer_count as f32).sqrt();
+
+        for frame in buffer.chunks_exact_mut(STEREO as usize) {
+            if self.is_finished() {
+                return;
+            }
+
+            let (mut left, mut right) = (0.0, 0.0);
+            for layer in self.layers.iter_mut() {
+                let sample = layer.next_sample(&second);
+                let (layer_left, layer_right) = layer.upmix.process(sample);
+                left += layer_left * stack_gain;
+                right += layer_right * stack_gain;
+            }
+
+            // Mix the sub-oscillator and noise generator in at the pan of the voice, ahead of the filter
+            let sub_noise_sample = self.sub.next_sample() * sub_noise.sub_level + self.noise.next_sample() * sub_noise.noise_level;
+            let (sub_noise_left, sub_noise_right) = self.upmix.process(sub_noise_sample);
+            left += sub_noise_left;
+            right += sub_noise_right;
+
+            let level = self.envelope.next_level() * AMPLITUDE * modulation.amplitude;
+            self.filter_envelope.next_level();
+
+            frame[0] += self.filters[0].process(left, filter.mode) * level;
+            frame[1] += self.filters[1].process(right, filter.mode) * level;
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use crate::envelopes::{EnvelopeStage, MAX_SEGMENT_TIME, MIN_SEGMENT_TIME};
+use crate::waveforms::SAMPLE_RATE;
+
+/// Attack, decay and release times in seconds along with the sustain level in the range [0.0, 1.0]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdsrParameters {
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub release: f32
+}
+
+impl AdsrParameters {
+    pub fn new(attack: f32, decay: f32, sustain: f32, release: f32) -> Self {
+        AdsrParameters {
+            attack: attack.clamp(MIN_SEGMENT_TIME, MAX_SEGMENT_TIME),
+            decay: decay.clamp(MIN_SEGMENT_TIME, MAX_SEGMENT_TIME),
+            sustain: sustain.clamp(0.0, 1.0),
+            release: release.clamp(MIN_SEGMENT_TIME, MAX_SEGMENT_TIME)
+        }
+    }
+}
+
+impl Default for AdsrParameters {
+    fn default() -> Self {
+        AdsrParameters::new(0.01, 0.15, 0.7, 0.25)
+    }
+}
+
+/// Linear attack/decay/sustain/release envelope generator producing one level per sample
+#[derive(Debug)]
+pub struct Adsr {
+    parameters: AdsrParameters,
+    stage: EnvelopeStage,
+    level: f32,
+    release_step: f32
+}
+
+impl Adsr {
+    /// Creates an envelope which immediately enters its attack stage.
+    pub fn new(parameters: AdsrParameters) -> Self {
+        Adsr { parameters, stage: EnvelopeStage::Attack, level: 0.0, release_step: 0.0 }
+    }
+
+    /// Replaces the times and sustain level, which take effect from the next sample onwards.
+    pub fn set_parameters(&mut self, parameters: AdsrParameters) {
+        self.parameters = parameters;
+    }
+
+    /// Returns the stage the envelope is currently in.
+    pub fn stage(&self) -> EnvelopeStage {
+        self.stage
+    }
+
+    /// Returns the level reached by the most recent sample.
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    /// Returns true once the release stage has faded out completely.
+    pub fn is_finished(&self) -> bool {
+        self.stage == EnvelopeStage::Idle
+    }
+
+    /// Enters the release stage, fading from the current level to silence over the release time.
+    pub fn release(&mut self) {
+        if self.stage != EnvelopeStage::Idle {
+            self.stage = EnvelopeStage::Release;
+            self.release_step = self.level / (self.parameters.release * SAMPLE_RATE);
+        }
+    }
+
+    /// Advances the envelope by one sample and returns its level in the range [0.0, 1.0].
+    pub fn next_level(&mut self) -> f32 {
+        match self.stage {
+            EnvelopeStage::Attack => {
+                self.level += 1.0 / (self.parameters.attack * SAMPLE_RATE);
+                if self.level >= 1.0 {
+                    self.level = 1.0;
+                    self.stage = EnvelopeStage::Decay;
+                }
+            }
+            EnvelopeStage::Decay => {
+                self.level -= (1.0 - self.parameters.sustain) / (self.parameters.decay * SAMPLE_RATE);
+                if self.level <= self.parameters.sustain {
+                    self.level = self.parameters.sustain;
+                    self.stage = EnvelopeStage::Sustain;
+                }
+            }
+            EnvelopeStage::Sustain => {
+                self.level = self.parameters.sustain;
+            }
+            EnvelopeStage::Release => {
+                self.level -= self.release_step;
+                if self.level <= 0.0 {
+                    self.level = 0.0;
+                    self.stage = EnvelopeStage::Idle;
+                }
+            }
+            EnvelopeStage::Idle => {
+                self.level = 0.0;
+            }
+        }
+
+        self.level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the number of samples a segment of the given time in seconds lasts.
+    fn samples(time: f32) -> usize {
+        (time * SAMPLE_RATE).round() as usize
+    }
+
+    #[test]
+    fn attack_reaches_full_level() {
+        let parameters = AdsrParameters::new(0.01, 0.1, 0.5, 0.2);
+        let mut envelope = Adsr::new(parameters);
+
+        // Rounding may take the attack a single sample longer than its time
+        let mut levels = Vec::new();
+        while envelope.stage() == EnvelopeStage::Attack && levels.len() <= samples(parameters.attack) + 1 {
+            levels.push(envelope.next_level());
+        }
+
+        assert!(levels.windows(2).all(|pair| pair[1] > pair[0]));
+        assert_eq!(levels.last(), Some(&1.0));
+        assert_eq!(envelope.stage(), EnvelopeStage::Decay);
+    }
+
+    #[test]
+    fn decay_settles_at_sustain() {
+        let parameters = AdsrParameters::new(0.01, 0.1, 0.5, 0.2);
+        let mut envelope = Adsr::new(parameters);
+
+        for _ in 0..samples(parameters.attack + parameters.decay) + 2 {
+            envelope.next_level();
+        }
+
+        assert_eq!(envelope.stage(), EnvelopeStage::Sustain);
+        for _ in 0..1000 {
+            assert_eq!(envelope.next_level(), parameters.sustain);
+        }
+    }
+
+    #[test]
+    fn release_during_attack_starts_at_current_level() {
+        let parameters = AdsrParameters::new(0.1, 0.1, 0.8, 0.2);
+        let mut envelope = Adsr::new(parameters);
+
+        for _ in 0..samples(parameters.attack) / 4 {
+            envelope.next_level();
+        }
+        let level = envelope.level();
+        assert!(level > 0.2 && level < 0.3);
+
+        envelope.release();
+        assert_eq!(envelope.stage(), EnvelopeStage::Release);
+
+        // The release fades from the level reached during the attack within the release time, never jumping
+        let mut previous = level;
+        for _ in 0..samples(parameters.release) {
+            let next = envelope.next_level();
+            assert!(next <= previous && previous - next < 0.001, "the level jumped from {} to {}", previous, next);
+            previous = next;
+        }
+        assert!(previous < 0.001);
+    }
+
+    #[test]
+    fn idle_state_ends_voice() {
+        let parameters = AdsrParameters::new(0.01, 0.1, 0.5, 0.2);
+        let mut envelope = Adsr::new(parameters);
+        for _ in 0..samples(parameters.attack) {
+            envelope.next_level();
+        }
+
+        envelope.release();
+        assert!(!envelope.is_finished());
+
+        for _ in 0..samples(parameters.release) + 1 {
+            envelope.next_level();
+        }
+
+        assert_eq!(envelope.stage(), EnvelopeStage::Idle);
+        assert!(envelope.is_finished());
+
+        // An idle envelope stays silent and cannot be released again
+        envelope.release();
+        assert_eq!(envelope.stage(), EnvelopeStage::Idle);
+        assert_eq!(envelope.next_level(), 0.0);
+    }
+}
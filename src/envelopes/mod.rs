@@ -0,0 +1,32 @@
+use std::fmt;
+
+pub mod adsr;
+
+/// Shortest time an envelope segment may be set to, in seconds
+pub const MIN_SEGMENT_TIME: f32 = 0.001;
+
+/// Longest time an envelope segment may be set to, in seconds
+pub const MAX_SEGMENT_TIME: f32 = 5.0;
+
+/// Enumerates the stages an envelope passes through from note-on to silence
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnvelopeStage {
+    Idle,
+    Attack,
+    Decay,
+    Sustain,
+    Release
+}
+
+/// Implements the [Display] trait for [EnvelopeStage]
+impl fmt::Display for EnvelopeStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvelopeStage::Idle => write!(f, "IDLE"),
+            EnvelopeStage::Attack => write!(f, "ATK"),
+            EnvelopeStage::Decay => write!(f, "DEC"),
+            EnvelopeStage::Sustain => write!(f, "SUS"),
+            EnvelopeStage::Release => write!(f, "REL")
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::envelopes::adsr::AdsrParameters;
+
+pub mod state_variable;
+
+pub const MIN_CUTOFF: f32 = 100.0;
+pub const MAX_CUTOFF: f32 = 12800.0;
+pub const CUTOFF_STEP: f32 = 2.0; // Cutoff is moved by one octave per step
+pub const MAX_RESONANCE: f32 = 1.0;
+pub const RESONANCE_STEP: f32 = 1.0 / 7.0;
+pub const MAX_FILTER_ENVELOPE_OCTAVES: f32 = 7.0; // At full amount, the filter envelope sweeps the cutoff across the whole knob range
+
+/// Enumerates the responses of the filter section, or whether it is bypassed altogether
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterMode {
+    Off,
+    LowPass,
+    HighPass,
+    BandPass,
+    Notch
+}
+
+impl FilterMode {
+    /// Returns the next filter response in the order low-pass, high-pass, band-pass and notch.
+    /// A bypassed filter is switched on in low-pass mode.
+    pub fn next(&self) -> FilterMode {
+        match self {
+            FilterMode::Off => FilterMode::LowPass,
+            FilterMode::LowPass => FilterMode::HighPass,
+            FilterMode::HighPass => FilterMode::BandPass,
+            FilterMode::BandPass => FilterMode::Notch,
+            FilterMode::Notch => FilterMode::LowPass
+        }
+    }
+}
+
+/// Settings shared by the filters of all voices
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FilterParameters {
+    pub mode: FilterMode,
+    pub cutoff: f32,
+    pub resonance: f32,
+    /// The envelope every voice sweeps its cutoff with.
+    pub envelope: AdsrParameters,
+    /// How far the envelope sweeps the cutoff at its peak, from [MAX_FILTER_ENVELOPE_OCTAVES] down at -1.0
+    /// to as many octaves up at 1.0.
+    pub envelope_amount: f32
+}
@@ -0,0 +1,71 @@
+use std::f32::consts::PI;
+
+use crate::filters::{FilterMode, MAX_RESONANCE};
+use crate::waveforms::SAMPLE_RATE;
+
+/// Damping applied at zero resonance, which corresponds to a Q of 0.5
+const MAX_DAMPING: f32 = 2.0;
+
+/// Damping applied at full resonance, which corresponds to a Q of 10
+const MIN_DAMPING: f32 = 0.1;
+
+/// Resonant two-pole multi-mode filter based on the topology-preserving transform (TPT) of the analog state-variable filter.
+///
+/// The state-variable structure yields low-pass, high-pass, band-pass and notch responses from the same
+/// integrator states, so switching modes does not reset the filter. Unlike naive digital filters, the trapezoidal integrators keep the filter stable and in tune
+/// all the way up to the Nyquist frequency, even while the cutoff is being changed.
+#[derive(Debug, Default)]
+pub struct StateVariableFilter {
+    k: f32,
+    a1: f32,
+    a2: f32,
+    a3: f32,
+    ic1eq: f32,
+    ic2eq: f32
+}
+
+impl StateVariableFilter {
+    /// Recomputes the filter coefficients for a cutoff in Hz and a resonance in the range [0.0, 1.0].
+    pub fn set_parameters(&mut self, cutoff: f32, resonance: f32) {
+        // Keep the cutoff safely below the Nyquist frequency, where the prewarping tangent diverges
+        let cutoff = cutoff.clamp(1.0, 0.49 * SAMPLE_RATE);
+        let resonance = resonance.clamp(0.0, MAX_RESONANCE) / MAX_RESONANCE;
+
+        // Prewarped integrator gain and damping (1/Q)
+        let g = (PI * cutoff / SAMPLE_RATE).tan();
+        let k = MAX_DAMPING - resonance * (MAX_DAMPING - MIN_DAMPING);
+
+        self.k = k;
+        self.a1 = 1.0 / (1.0 + g * (g + k));
+        self.a2 = g * self.a1;
+        self.a3 = g * self.a2;
+    }
+
+    /// Filters a single sample and returns the output of the given response. A bypassed filter returns the input unchanged.
+    pub fn process(&mut self, input: f32, mode: FilterMode) -> f32 {
+        if mode == FilterMode::Off {
+            return input;
+        }
+
+        let v3 = input - self.ic2eq;
+        let v1 = self.a1 * self.ic1eq + self.a2 * v3;
+        let v2 = self.ic2eq + self.a2 * self.ic1eq + self.a3 * v3;
+
+        // Update the integrator states
+        self.ic1eq = 2.0 * v1 - self.ic1eq;
+        self.ic2eq = 2.0 * v2 - self.ic2eq;
+
+        // Derive the requested response from the band-pass (v1) and low-pass (v2) outputs
+        let low = v2;
+        let band = v1;
+        let high = input - self.k * v1 - v2;
+
+        match mode {
+            FilterMode::LowPass => low,
+            FilterMode::HighPass => high,
+            FilterMode::BandPass => band,
+            FilterMode::Notch => low + high,
+            FilterMode::Off => input
+        }
+    }
+}
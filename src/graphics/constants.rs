@@ -2,9 +2,38 @@
 pub const WINDOW_WIDTH: usize = 575;
 pub const WINDOW_HEIGHT: usize = 496;
 
+// Color of the traces drawn on the waveform display
+pub const DISPLAY_TRACE_COLOR: u32 = 0xFF39FF0F;
+
+// Number of samples per period rendered for the traces drawn on the waveform display
+pub const TRACE_RESOLUTION: usize = 256;
+
+// Constants for the knob sprites and their placement on the rack, where the filter cutoff knob sits in the column
+// of knob sprites given below, followed by the resonance knob. The sub and noise level knobs sit in the same columns
+// right above them, leaving room for their labels, which overlap the empty top margin of the knob sprites
+pub const KNOB_WIDTH: usize = 64;
+pub const KNOB_HEIGHT: usize = 48;
+pub const KNOB_LABEL_OFFSET: usize = 8;
+pub const FILTER_KNOB_COLUMN: usize = 6;
+pub const FILTER_KNOB_Y: usize = 5 * KNOB_HEIGHT - 10;
+pub const SUB_NOISE_KNOB_Y: usize = FILTER_KNOB_Y - KNOB_HEIGHT - KNOB_LABEL_OFFSET;
+
 // Constants for waveforms
 pub const WAVEFORM_SINE: usize = 0;
 pub const WAVEFORM_SQUARE: usize = 1;
+pub const WAVEFORM_SAWTOOTH: usize = 2;
+pub const WAVEFORM_TRIANGLE: usize = 3;
+pub const WAVEFORM_PULSE: usize = 4;
+pub const WAVEFORM_WHITE_NOISE: usize = 5;
+pub const WAVEFORM_PINK_NOISE: usize = 6;
+pub const WAVEFORM_WAVETABLE: usize = 7;
+pub const WAVEFORM_FM: usize = 8;
+
+// Constants for filter modes
+pub const FILTER_MODE_LOW_PASS: usize = 0;
+pub const FILTER_MODE_HIGH_PASS: usize = 1;
+pub const FILTER_MODE_BAND_PASS: usize = 2;
+pub const FILTER_MODE_NOTCH: usize = 3;
 
 // Constants for keys
 pub const KEY_IDLE: usize = 0;
@@ -1,5 +1,7 @@
 use image::GenericImageView;
 
+use crate::graphics::constants::{KNOB_HEIGHT, KNOB_WIDTH};
+
 pub struct Sprite {
     pub(crate) width: u32,  // Width of the sprite in pixels
     pub(crate) height: u32, // Height of the sprite in pixels
@@ -19,10 +21,11 @@ pub struct Sprites {
     pub tangents: Vec<Sprite>,
     pub knob: Vec<Sprite>,
     pub rack: Vec<Sprite>,
-    pub display_sine: Vec<Sprite>,
-    pub display_square: Vec<Sprite>,
+    pub displays: Vec<Vec<Sprite>>, // Indexed by the WAVEFORM_* constants
     pub octave_fader: Vec<Sprite>,
-    pub bulb: Vec<Sprite>
+    pub bulb: Vec<Sprite>,
+    pub filter_modes: Vec<Sprite>,
+    pub font: Vec<Sprite>
 }
 
 impl Sprites {
@@ -32,12 +35,23 @@ impl Sprites {
             numbers: load_sprites_from_map("assets/numbers.png", 64, 48),
             keys: load_sprites_from_map("assets/keys.png", 64, 144),
             tangents: load_sprites_from_map("assets/tangents.png", 30, 96),
-            knob: load_sprites_from_map("assets/knob.png", 64, 48),
-            display_sine: load_sprites_from_map("assets/display_sine.png", 164, 51),
-            display_square: load_sprites_from_map("assets/display_square.png", 164, 51),
+            knob: load_sprites_from_map("assets/knob.png", KNOB_WIDTH as u32, KNOB_HEIGHT as u32),
+            displays: [
+                "assets/display_sine.png",
+                "assets/display_square.png",
+                "assets/display_sawtooth.png",
+                "assets/display_triangle.png",
+                "assets/display_pulse.png",
+                "assets/display_white_noise.png",
+                "assets/display_pink_noise.png",
+                "assets/display_wavetable.png",
+                "assets/display_fm.png"
+            ].iter().map(|path| load_sprites_from_map(path, 164, 51)).collect(),
             rack: load_sprites_from_map("assets/rack.png", 600, 496),
             octave_fader: load_sprites_from_map("assets/octave_fader.png", 28, 143),
-            bulb: load_sprites_from_map("assets/bulb.png", 12, 12)
+            bulb: load_sprites_from_map("assets/bulb.png", 12, 12),
+            filter_modes: load_sprites_from_map("assets/filter_modes.png", 16, 12),
+            font: load_sprites_from_map("assets/font.png", 7, 12)
         }
     }
 }
@@ -56,7 +70,7 @@ impl Sprites {
 /// A vector containing tuples of sprite dimensions and pixel data.
 pub fn load_sprites_from_map(sprite_map_path: &str, sprite_width: u32, sprite_height: u32) -> Vec<Sprite> {
     // Load the sprite map image
-    let sprite_map = image::open(sprite_map_path).expect(&format!("Failed to open sprite map at {}", sprite_map_path));
+    let sprite_map = image::open(sprite_map_path).unwrap_or_else(|_| panic!("Failed to open sprite map at {}", sprite_map_path));
     let (map_width, map_height) = sprite_map.dimensions();
 
     println!("Sprite map loaded from {}", sprite_map_path);
@@ -1,24 +1,32 @@
-use rodio::{OutputStream, Sink};
+use rodio::OutputStream;
 
 use crate::{
+    engine::Engine,
     state::{event_loop::start_event_loop, State},
-    graphics::sprites::Sprites
+    graphics::sprites::Sprites,
+    waveforms::wavetable::load_wavetables
 };
 
-mod waveforms;mod state;mod music_theory;mod graphics;
+mod waveforms;mod state;mod music_theory;mod graphics;mod engine;mod envelopes;mod filters;mod effects;mod modulation;
 
 fn main() {
 
-    // Initialize the audio output stream and sink
+    // Initialize the audio output stream
     let (_stream, stream_handle) = OutputStream::try_default().unwrap();
-    let mut sink = Sink::try_new(&stream_handle).unwrap();
+
+    // Instantiate the polyphonic voice engine and hand its mixer source to the output stream
+    let engine = Engine::new();
+    stream_handle.play_raw(engine.source()).unwrap();
 
     // Instantiate the Sprites struct, which in turn will load sprites from sprite maps into 3d Vectors
     let sprites = Sprites::new();
 
+    // Load the single-cycle wavetables which the wavetable oscillator may play
+    let wavetables = load_wavetables("assets/wavetables");
+
     // Instantiate the state struct with default values for octave and waveform
-    let mut state = State::new();
+    let mut state = State::new(wavetables);
 
     // Execute the main event loop, which handles user input and associated sound generation
-    start_event_loop(&mut state, &mut sink, &sprites);
+    start_event_loop(&mut state, &engine, &sprites);
 }
\ No newline at end of file
@@ -0,0 +1,42 @@
+use crate::modulation::{LfoParameters, LfoShape};
+use crate::waveforms::noise::WhiteNoise;
+use crate::waveforms::sine_wave::calculate_sine;
+use crate::waveforms::Oscillator;
+
+/// Low-frequency oscillator shared by all voices, advanced once per rendered block.
+///
+/// As its rate lies far below the audible range, the LFO is neither band-limited nor evaluated per sample;
+/// a block of [crate::engine::BLOCK_SIZE] frames lasts little more than a millisecond.
+#[derive(Debug)]
+pub struct Lfo {
+    phase: f32,
+    held: f32,
+    random: WhiteNoise
+}
+
+impl Lfo {
+    pub fn new(seed: u32) -> Self {
+        let mut random = WhiteNoise::new(seed);
+        Lfo { phase: 0.0, held: random.next_sample(), random }
+    }
+
+    /// Advances the LFO by the given time in seconds and returns its output in the range [-1.0, 1.0].
+    pub fn advance(&mut self, parameters: &LfoParameters, tempo: f32, seconds: f32) -> f32 {
+        self.phase += parameters.frequency(tempo) * seconds;
+
+        // Sample and hold picks a new random value at the start of every period
+        if self.phase >= 1.0 {
+            self.phase -= self.phase.floor();
+            self.held = self.random.next_sample();
+        }
+
+        let phase = self.phase;
+        match parameters.shape {
+            LfoShape::Sine => calculate_sine(phase),
+            LfoShape::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+            LfoShape::Square => if phase < 0.5 { 1.0 } else { -1.0 },
+            LfoShape::Sawtooth => 2.0 * phase - 1.0,
+            LfoShape::SampleAndHold => self.held
+        }
+    }
+}
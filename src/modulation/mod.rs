@@ -0,0 +1,340 @@
+use std::fmt;
+
+pub mod lfo;
+
+/// Number of LFOs available on the synthesizer
+pub const LFO_COUNT: usize = 2;
+
+pub const MIN_LFO_RATE: f32 = 0.05;
+pub const MAX_LFO_RATE: f32 = 20.0;
+pub const DEFAULT_LFO_RATE: f32 = 5.0;
+
+pub const MIN_TEMPO: f32 = 40.0;
+pub const MAX_TEMPO: f32 = 240.0;
+pub const DEFAULT_TEMPO: f32 = 120.0;
+
+/// Number of slots in the modulation matrix
+pub const MODULATION_SLOTS: usize = 8;
+
+/// Deviation in semitones at full amount when modulating the pitch
+pub const MAX_PITCH_MODULATION: f32 = 12.0;
+
+/// Deviation in octaves at full amount when modulating the filter cutoff
+pub const MAX_CUTOFF_MODULATION: f32 = 4.0;
+
+/// Deviation at full amount when modulating the pulse width
+pub const MAX_PULSE_WIDTH_MODULATION: f32 = 0.45;
+
+/// Note lengths a tempo-synced LFO may complete a period in, along with their length in beats
+pub const LFO_DIVISIONS: [(&str, f32); 7] = [
+    ("2/1", 8.0),
+    ("1/1", 4.0),
+    ("1/2", 2.0),
+    ("1/4", 1.0),
+    ("1/8", 0.5),
+    ("1/16", 0.25),
+    ("1/32", 0.125)
+];
+
+/// Enumerates the waveforms an LFO may oscillate with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LfoShape {
+    Sine,
+    Triangle,
+    Square,
+    Sawtooth,
+    SampleAndHold
+}
+
+impl LfoShape {
+    /// Returns the next shape, wrapping around after sample and hold.
+    pub fn next(&self) -> LfoShape {
+        match self {
+            LfoShape::Sine => LfoShape::Triangle,
+            LfoShape::Triangle => LfoShape::Square,
+            LfoShape::Square => LfoShape::Sawtooth,
+            LfoShape::Sawtooth => LfoShape::SampleAndHold,
+            LfoShape::SampleAndHold => LfoShape::Sine
+        }
+    }
+}
+
+/// Implements the [Display] trait for [LfoShape]
+impl fmt::Display for LfoShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LfoShape::Sine => write!(f, "SIN"),
+            LfoShape::Triangle => write!(f, "TRI"),
+            LfoShape::Square => write!(f, "SQR"),
+            LfoShape::Sawtooth => write!(f, "SAW"),
+            LfoShape::SampleAndHold => write!(f, "S&H")
+        }
+    }
+}
+
+/// Enumerates the signals which may modulate a parameter through the modulation matrix
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModulationSource {
+    Lfo1,
+    Lfo2,
+    /// The level of the amplitude envelope of the voice.
+    Envelope,
+    /// The velocity the voice was started with.
+    Velocity,
+    ModWheel
+}
+
+impl ModulationSource {
+    /// Returns the next source, wrapping around after the mod wheel.
+    pub fn next(&self) -> ModulationSource {
+        match self {
+            ModulationSource::Lfo1 => ModulationSource::Lfo2,
+            ModulationSource::Lfo2 => ModulationSource::Envelope,
+            ModulationSource::Envelope => ModulationSource::Velocity,
+            ModulationSource::Velocity => ModulationSource::ModWheel,
+            ModulationSource::ModWheel => ModulationSource::Lfo1
+        }
+    }
+
+    /// Returns true for sources which swing between -1.0 and 1.0 rather than ranging from 0.0 to 1.0.
+    pub fn is_bipolar(&self) -> bool {
+        matches!(self, ModulationSource::Lfo1 | ModulationSource::Lfo2)
+    }
+}
+
+/// Implements the [Display] trait for [ModulationSource]
+impl fmt::Display for ModulationSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModulationSource::Lfo1 => write!(f, "LFO1"),
+            ModulationSource::Lfo2 => write!(f, "LFO2"),
+            ModulationSource::Envelope => write!(f, "ENV"),
+            ModulationSource::Velocity => write!(f, "VEL"),
+            ModulationSource::ModWheel => write!(f, "WHL")
+        }
+    }
+}
+
+/// Enumerates the parameters which may be modulated through the modulation matrix, or whether a slot is unused
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModulationDestination {
+    Off,
+    Pitch,
+    Cutoff,
+    Resonance,
+    Amplitude,
+    PulseWidth,
+    OscillatorMix,
+    Pan,
+    SubLevel,
+    NoiseLevel,
+    UnisonDetune,
+    WavetablePosition
+}
+
+impl ModulationDestination {
+    /// Returns the next destination, wrapping around to off after the wavetable position.
+    pub fn next(&self) -> ModulationDestination {
+        match self {
+            ModulationDestination::Off => ModulationDestination::Pitch,
+            ModulationDestination::Pitch => ModulationDestination::Cutoff,
+            ModulationDestination::Cutoff => ModulationDestination::Resonance,
+            ModulationDestination::Resonance => ModulationDestination::Amplitude,
+            ModulationDestination::Amplitude => ModulationDestination::PulseWidth,
+            ModulationDestination::PulseWidth => ModulationDestination::OscillatorMix,
+            ModulationDestination::OscillatorMix => ModulationDestination::Pan,
+            ModulationDestination::Pan => ModulationDestination::SubLevel,
+            ModulationDestination::SubLevel => ModulationDestination::NoiseLevel,
+            ModulationDestination::NoiseLevel => ModulationDestination::UnisonDetune,
+            ModulationDestination::UnisonDetune => ModulationDestination::WavetablePosition,
+            ModulationDestination::WavetablePosition => ModulationDestination::Off
+        }
+    }
+}
+
+/// Implements the [Display] trait for [ModulationDestination]
+impl fmt::Display for ModulationDestination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModulationDestination::Off => write!(f, "OFF"),
+            ModulationDestination::Pitch => write!(f, "PIT"),
+            ModulationDestination::Cutoff => write!(f, "CUT"),
+            ModulationDestination::Resonance => write!(f, "RES"),
+            ModulationDestination::Amplitude => write!(f, "AMP"),
+            ModulationDestination::PulseWidth => write!(f, "PW"),
+            ModulationDestination::OscillatorMix => write!(f, "MIX"),
+            ModulationDestination::Pan => write!(f, "PAN"),
+            ModulationDestination::SubLevel => write!(f, "SUB"),
+            ModulationDestination::NoiseLevel => write!(f, "NSE"),
+            ModulationDestination::UnisonDetune => write!(f, "DET"),
+            ModulationDestination::WavetablePosition => write!(f, "WTP")
+        }
+    }
+}
+
+/// Settings of a single LFO
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LfoParameters {
+    pub shape: LfoShape,
+    /// Rate in Hz while the LFO runs freely.
+    pub rate: f32,
+    /// Index into [LFO_DIVISIONS] of the note length of a period while the LFO is synced to the tempo, if it is.
+    pub division: Option<usize>
+}
+
+impl LfoParameters {
+    /// Returns the rate in Hz, which for a tempo-synced LFO follows from the tempo in beats per minute.
+    pub fn frequency(&self, tempo: f32) -> f32 {
+        match self.division {
+            Some(division) => tempo / 60.0 / LFO_DIVISIONS[division].1,
+            None => self.rate
+        }
+    }
+}
+
+impl Default for LfoParameters {
+    fn default() -> Self {
+        LfoParameters { shape: LfoShape::Sine, rate: DEFAULT_LFO_RATE, division: None }
+    }
+}
+
+/// A route of the modulation matrix, modulating a destination by a source
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModulationSlot {
+    pub source: ModulationSource,
+    pub destination: ModulationDestination,
+    /// Amount of the modulation in the range [-1.0, 1.0], where negative amounts invert the source.
+    pub amount: f32
+}
+
+impl ModulationSlot {
+    /// Returns true if the slot modulates a destination.
+    pub fn is_active(&self) -> bool {
+        self.destination != ModulationDestination::Off && self.amount != 0.0
+    }
+}
+
+impl Default for ModulationSlot {
+    fn default() -> Self {
+        ModulationSlot { source: ModulationSource::Lfo1, destination: ModulationDestination::Off, amount: 0.5 }
+    }
+}
+
+/// Settings of all LFOs along with the tempo that synced LFOs follow, the routes of the modulation matrix
+/// and the position of the mod wheel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModulationParameters {
+    pub lfos: [LfoParameters; LFO_COUNT],
+    /// Tempo in beats per minute.
+    pub tempo: f32,
+    pub slots: [ModulationSlot; MODULATION_SLOTS],
+    /// Position of the mod wheel in the range [0.0, 1.0].
+    pub mod_wheel: f32
+}
+
+impl Default for ModulationParameters {
+    fn default() -> Self {
+        let mut slots = [ModulationSlot::default(); MODULATION_SLOTS];
+
+        // Scale the level of every voice by its velocity, as on most synthesizers
+        slots[0] = ModulationSlot { source: ModulationSource::Velocity, destination: ModulationDestination::Amplitude, amount: 1.0 };
+
+        ModulationParameters { lfos: [LfoParameters::default(); LFO_COUNT], tempo: DEFAULT_TEMPO, slots, mod_wheel: 0.0 }
+    }
+}
+
+/// The current values of all modulation sources
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModulationSources {
+    pub lfos: [f32; LFO_COUNT],
+    pub envelope: f32,
+    pub velocity: f32,
+    pub mod_wheel: f32
+}
+
+impl ModulationSources {
+    /// Returns the value of the given source.
+    pub fn value(&self, source: ModulationSource) -> f32 {
+        match source {
+            ModulationSource::Lfo1 => self.lfos[0],
+            ModulationSource::Lfo2 => self.lfos[1],
+            ModulationSource::Envelope => self.envelope,
+            ModulationSource::Velocity => self.velocity,
+            ModulationSource::ModWheel => self.mod_wheel
+        }
+    }
+}
+
+/// The deviations of the modulated parameters from their set values, summed over all routes of the modulation matrix
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModulationOffsets {
+    /// Pitch deviation in semitones.
+    pub pitch: f32,
+    /// Cutoff deviation in octaves.
+    pub cutoff: f32,
+    /// Resonance deviation.
+    pub resonance: f32,
+    /// Factor the level of the voice is multiplied with.
+    pub amplitude: f32,
+    /// Deviation of the duty cycle of pulse waves.
+    pub pulse_width: f32,
+    /// Deviation of the balance between the oscillators.
+    pub oscillator_mix: f32,
+    /// Deviation of the pan.
+    pub pan: f32,
+    /// Deviation of the level of the sub-oscillator.
+    pub sub_level: f32,
+    /// Deviation of the level of the noise generator.
+    pub noise_level: f32,
+    /// Deviation of the unison detune, as a fraction of the maximum detune.
+    pub unison_detune: f32,
+    /// Deviation of the read position within wavetables.
+    pub wavetable_position: f32
+}
+
+impl ModulationOffsets {
+    /// Sums the deviations caused by the routes of the modulation matrix for the given source values.
+    pub fn evaluate(slots: &[ModulationSlot], sources: &ModulationSources) -> Self {
+        let mut offsets = ModulationOffsets {
+            pitch: 0.0,
+            cutoff: 0.0,
+            resonance: 0.0,
+            amplitude: 1.0,
+            pulse_width: 0.0,
+            oscillator_mix: 0.0,
+            pan: 0.0,
+            sub_level: 0.0,
+            noise_level: 0.0,
+            unison_detune: 0.0,
+            wavetable_position: 0.0
+        };
+
+        for slot in slots.iter().filter(|slot| slot.is_active()) {
+            let value = sources.value(slot.source);
+            let deviation = value * slot.amount;
+
+            match slot.destination {
+                ModulationDestination::Off => {}
+                ModulationDestination::Pitch => offsets.pitch += deviation * MAX_PITCH_MODULATION,
+                ModulationDestination::Cutoff => offsets.cutoff += deviation * MAX_CUTOFF_MODULATION,
+                ModulationDestination::Resonance => offsets.resonance += deviation,
+                ModulationDestination::Amplitude => {
+                    // The level is only ever attenuated, following the source from silence at its minimum to full
+                    // level at its maximum, or the other way round for negative amounts
+                    let level = if slot.source.is_bipolar() { 0.5 * (1.0 + value) } else { value };
+                    let level = if slot.amount < 0.0 { 1.0 - level } else { level };
+                    offsets.amplitude *= 1.0 - slot.amount.abs() * (1.0 - level);
+                }
+                ModulationDestination::PulseWidth => offsets.pulse_width += deviation * MAX_PULSE_WIDTH_MODULATION,
+                ModulationDestination::OscillatorMix => offsets.oscillator_mix += deviation,
+                ModulationDestination::Pan => offsets.pan += deviation,
+                ModulationDestination::SubLevel => offsets.sub_level += deviation,
+                ModulationDestination::NoiseLevel => offsets.noise_level += deviation,
+                ModulationDestination::UnisonDetune => offsets.unison_detune += deviation,
+                ModulationDestination::WavetablePosition => offsets.wavetable_position += deviation
+            }
+        }
+
+        offsets
+    }
+}
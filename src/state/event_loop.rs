@@ -2,9 +2,8 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 use minifb::{Key as key, Window, WindowOptions};
-use rodio::Sink;
-
 use crate::{
+    engine::Engine,
     graphics::constants::*,
     graphics::sprites::*,
     state::{FRAME_DURATION, State},
@@ -15,7 +14,7 @@ use crate::state::utils::{draw_buffer, handle_key_presses, update_buffer_with_st
 ///
 /// # Parameters
 /// - `state`: Mutable reference to `SynthesizerState`, which manages the current state of the synthesizer.
-/// - `sink`: Mutable reference to `Sink`, the audio sink responsible for playing sound.
+/// - `engine`: Reference to the `Engine`, the polyphonic voice engine responsible for playing sound.
 /// - `sprites`: Reference to `Sprites`, containing all graphical assets used for rendering visuals.
 ///
 /// # Event Loop Logic
@@ -24,7 +23,7 @@ use crate::state::utils::{draw_buffer, handle_key_presses, update_buffer_with_st
 /// - Updates the visual representation of the synthesizer based on the current state.
 /// - Renders the updated visual buffer onto the window.
 /// - Maintains a frame rate of approximately 60 frames per second by calculating necessary sleep time.
-pub fn start_event_loop(state: &mut State, sink: &mut Sink, sprites: &Sprites) {
+pub fn start_event_loop(state: &mut State, engine: &Engine, sprites: &Sprites) {
     // Create a window with error handling
     let mut window = Window::new(
         "Rust Synthesizer 0.5",
@@ -49,7 +48,49 @@ pub fn start_event_loop(state: &mut State, sink: &mut Sink, sprites: &Sprites) {
         let start = Instant::now(); // Record start time for frame timing
 
         // Handle user key presses to update synthesizer state and play sound
-        handle_key_presses(state, &mut window, sink);
+        handle_key_presses(state, &mut window, engine);
+
+        // Hand the current filter settings to the voice engine, which applies them to all sounding voices
+        engine.set_filter(state.filter_parameters());
+
+        // Hand the settings of the second oscillator to the voice engine
+        engine.set_second_oscillator(state.second_oscillator_parameters());
+
+        // Hand the tuning and levels of the sub-oscillator and noise layer to the voice engine
+        engine.set_sub_noise(state.sub_noise_parameters());
+
+        // Hand the wavetable position to the voice engine, so that sounding notes morph along with the knob
+        engine.set_wavetable_position(state.wavetable_position());
+
+        // Hand the FM operators to the voice engine, so that sounding notes follow them while they are edited
+        engine.set_fm(state.fm_parameters());
+
+        // Hand the detune and stereo spread of the unison stack to the voice engine
+        engine.set_unison(state.unison_parameters());
+
+        // Hand the stereo width to the master bus of the voice engine
+        engine.set_stereo(state.stereo_parameters());
+
+        // Hand the order, bypass and settings of the master effects chain to the voice engine
+        engine.set_effects(state.effects_parameters());
+
+        // Hand the bands of the equalizer on the master output to the voice engine
+        engine.set_equalizer(state.equalizer_parameters());
+
+        // Hand the settings of the LFOs to the voice engine
+        engine.set_modulation(state.modulation_parameters());
+
+        // Hand the pitch bend of the direction keys to the voice engine
+        engine.set_pitch_bend(state.pitch_bend_semitones());
+
+        // Mirror the envelope stage of the latest voice so it can be shown on the display
+        state.update_envelope_stage(engine.latest_envelope_stage());
+
+        // Mirror the modulated cutoff so the cutoff knob follows the LFOs in real time
+        state.update_modulated_cutoff(engine.modulated_cutoff());
+
+        // Mirror the gain reduction of the master limiter so it can be shown below the effects chain
+        state.update_gain_reduction(engine.gain_reduction());
 
         // Change rack index every 2 seconds by toggling between 0 and 1
         if last_rack_change.elapsed() >= Duration::from_secs(2) {
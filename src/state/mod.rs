@@ -1,45 +1,245 @@
+use std::fmt;
+use std::sync::Arc;
 use std::time::Duration;
 
 use minifb::Key;
 
-use crate::graphics::constants::{WAVEFORM_SINE, WAVEFORM_SQUARE};
 use crate::music_theory::{OCTAVE_LOWER_BOUND, OCTAVE_UPPER_BOUND};
+use crate::effects::{ChainEffect, EffectKind, EffectsParameters, EQ_BANDS, EqParameters, MAX_CHORUS_RATE, MAX_COMPRESSOR_MAKEUP, MAX_COMPRESSOR_RATIO,
+                     MAX_COMPRESSOR_TIME, MAX_CRUSHER_BITS, MAX_CRUSHER_DOWNSAMPLE, MAX_DELAY_FEEDBACK, MAX_DELAY_TIME, MAX_DISTORTION_DRIVE,
+                     MAX_EQ_FREQUENCY, MAX_EQ_GAIN, MAX_EQ_Q, MAX_STEREO_WIDTH, MIN_CHORUS_RATE, MIN_COMPRESSOR_THRESHOLD, MIN_COMPRESSOR_TIME,
+                     MIN_CRUSHER_BITS, MIN_DELAY_TIME, MIN_EQ_FREQUENCY, MIN_EQ_Q, SidechainMode};
+use crate::effects::equalizer::Equalizer;
+use crate::engine::{MAX_GLIDE_TIME, MAX_UNISON_DETUNE, MAX_UNISON_VOICES, MIN_GLIDE_TIME, PITCH_BEND_RANGE, StereoParameters, UnisonParameters};
+use crate::engine::voice::VoiceId;
+use crate::envelopes::adsr::AdsrParameters;
+use crate::envelopes::EnvelopeStage;
+use crate::filters::{CUTOFF_STEP, FilterMode, FilterParameters, MAX_CUTOFF, MAX_RESONANCE, MIN_CUTOFF, RESONANCE_STEP};
+use crate::modulation::{LFO_COUNT, LFO_DIVISIONS, MAX_LFO_RATE, MAX_TEMPO, MIN_LFO_RATE, MIN_TEMPO, MODULATION_SLOTS, ModulationParameters, ModulationSlot};
 use crate::music_theory::note::Note;
-use crate::waveforms::Waveform;
+use crate::waveforms::fm::{FM_OPERATORS, FmParameters, MAX_FM_FEEDBACK, MAX_FM_INDEX, MAX_FM_RATIO, MIN_FM_RATIO};
+use crate::waveforms::pulse_wave::{DEFAULT_PULSE_WIDTH, MAX_PULSE_WIDTH, MIN_PULSE_WIDTH};
+use crate::waveforms::wavetable::Wavetable;
+use crate::waveforms::{MAX_COARSE_DETUNE, MAX_FINE_DETUNE, MAX_SUB_OCTAVES, SecondOscillatorParameters, SubNoiseParameters};
+use crate::waveforms::registry::{OscillatorSettings, WaveformDefinition, WAVEFORMS};
 
 pub mod event_loop;
 mod utils;
 
 const FRAME_DURATION: Duration = Duration::from_millis(16); // Approximately 60Hz refresh rate
+const ENVELOPE_TIME_FACTOR: f32 = 1.25; // Envelope times are scaled by this factor per Up/Down key press
+const ENVELOPE_SUSTAIN_STEP: f32 = 0.05; // Sustain level is changed by this amount per Up/Down key press
+const PULSE_WIDTH_STEP: f32 = 0.05; // Pulse width is changed by this amount per Up/Down key press
+const WAVETABLE_POSITION_STEP: f32 = 0.05; // Wavetable position is changed by this amount per Up/Down key press
+const OSCILLATOR_MIX_STEP: f32 = 0.05; // Oscillator mix is changed by this amount per Up/Down key press
+const SUB_NOISE_LEVEL_STEP: f32 = 0.05; // Sub-oscillator and noise levels are changed by this amount per Up/Down key press
+const FM_RATIO_STEP: f32 = 0.5; // Operator ratio is changed by this amount per Up/Down key press
+const FM_INDEX_STEP: f32 = 0.25; // Operator modulation index is changed by this amount per Up/Down key press
+const FM_FEEDBACK_STEP: f32 = 0.05; // Operator feedback is changed by this amount per Up/Down key press
+const MODULATION_AMOUNT_STEP: f32 = 0.05; // Amount of a modulation route is changed by this amount per Up/Down key press
+const MOD_WHEEL_STEP: f32 = 0.05; // Mod wheel is moved by this amount per PageUp/PageDown key press
+const VELOCITY_STEP: f32 = 0.05; // Velocity of new notes is changed by this amount per -/= key press
+const FILTER_ENVELOPE_AMOUNT_STEP: f32 = 0.05; // Filter envelope amount is changed by this amount per Up/Down key press
+const TEMPO_STEP: f32 = 1.0; // Tempo in beats per minute is changed by this amount per Up/Down key press
+const UNISON_DETUNE_STEP: f32 = 2.0; // Unison detune in cents is changed by this amount per Up/Down key press
+const UNISON_SPREAD_STEP: f32 = 0.1; // Unison stereo spread is changed by this amount per Up/Down key press
+const PAN_STEP: f32 = 0.1; // Pan of new voices is changed by this amount per Up/Down key press
+const STEREO_WIDTH_STEP: f32 = 0.1; // Stereo width of the master bus is changed by this amount per Up/Down key press
+const DISTORTION_DRIVE_STEP: f32 = 1.5; // Distortion drive in decibels is changed by this amount per Up/Down key press
+const COMPRESSOR_LEVEL_STEP: f32 = 1.0; // Compressor threshold and makeup gain in decibels are changed by this amount per Up/Down key press
+const EQ_GAIN_STEP: f32 = 0.5; // Gain of an equalizer band in decibels is changed by this amount per Up/Down key press
+const EFFECT_LEVEL_STEP: f32 = 0.05; // Mix, feedback, size, damping and depth of the effects are changed by this amount per Up/Down key press
+
+/// Enumerates the parameters which may be selected and then adjusted with the Up/Down keys
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Parameter {
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+    FilterAttack,
+    FilterDecay,
+    FilterSustain,
+    FilterRelease,
+    FilterEnvelopeAmount,
+    PulseWidth,
+    WavetablePosition,
+    OscillatorMix,
+    CoarseDetune,
+    FineDetune,
+    SubLevel,
+    SubOctaves,
+    NoiseLevel,
+    FmRatio,
+    FmIndex,
+    FmFeedback,
+    LfoRate,
+    Tempo,
+    ModulationAmount,
+    GlideTime,
+    UnisonVoices,
+    UnisonDetune,
+    UnisonSpread,
+    Pan,
+    StereoWidth,
+    EffectMix,
+    DelayTime,
+    DelayFeedback,
+    ReverbSize,
+    ReverbDamping,
+    ChorusRate,
+    ChorusDepth,
+    DistortionDrive,
+    CrusherBits,
+    CrusherDownsample,
+    CompressorThreshold,
+    CompressorRatio,
+    CompressorAttack,
+    CompressorRelease,
+    CompressorMakeup,
+    EqFrequency,
+    EqGain,
+    EqQ
+}
+
+/// Implements the [Display] trait for [Parameter]
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Parameter::Attack => write!(f, "Attack"),
+            Parameter::Decay => write!(f, "Decay"),
+            Parameter::Sustain => write!(f, "Sustain"),
+            Parameter::Release => write!(f, "Release"),
+            Parameter::FilterAttack => write!(f, "Flt Atk"),
+            Parameter::FilterDecay => write!(f, "Flt Dec"),
+            Parameter::FilterSustain => write!(f, "Flt Sus"),
+            Parameter::FilterRelease => write!(f, "Flt Rel"),
+            Parameter::FilterEnvelopeAmount => write!(f, "Flt Env"),
+            Parameter::PulseWidth => write!(f, "PW"),
+            Parameter::WavetablePosition => write!(f, "Morph"),
+            Parameter::OscillatorMix => write!(f, "Osc mix"),
+            Parameter::CoarseDetune => write!(f, "Coarse"),
+            Parameter::FineDetune => write!(f, "Fine"),
+            Parameter::SubLevel => write!(f, "Sub"),
+            Parameter::SubOctaves => write!(f, "Sub oct"),
+            Parameter::NoiseLevel => write!(f, "Noise"),
+            Parameter::FmRatio => write!(f, "FM Ratio"),
+            Parameter::FmIndex => write!(f, "FM Index"),
+            Parameter::FmFeedback => write!(f, "FM Fdbk"),
+            Parameter::LfoRate => write!(f, "LFO Rate"),
+            Parameter::Tempo => write!(f, "Tempo"),
+            Parameter::ModulationAmount => write!(f, "Amount"),
+            Parameter::GlideTime => write!(f, "Glide"),
+            Parameter::UnisonVoices => write!(f, "Unison"),
+            Parameter::UnisonDetune => write!(f, "Detune"),
+            Parameter::UnisonSpread => write!(f, "Spread"),
+            Parameter::Pan => write!(f, "Pan"),
+            Parameter::StereoWidth => write!(f, "Stereo"),
+            Parameter::EffectMix => write!(f, "Mix"),
+            Parameter::DelayTime => write!(f, "Time"),
+            Parameter::DelayFeedback => write!(f, "Dly Fdbk"),
+            Parameter::ReverbSize => write!(f, "Size"),
+            Parameter::ReverbDamping => write!(f, "Damp"),
+            Parameter::ChorusRate => write!(f, "Chorus"),
+            Parameter::ChorusDepth => write!(f, "Depth"),
+            Parameter::DistortionDrive => write!(f, "Drive"),
+            Parameter::CrusherBits => write!(f, "Bits"),
+            Parameter::CrusherDownsample => write!(f, "Downsmp"),
+            Parameter::CompressorThreshold => write!(f, "Thresh"),
+            Parameter::CompressorRatio => write!(f, "Cmp Ratio"),
+            Parameter::CompressorAttack => write!(f, "Cmp Atk"),
+            Parameter::CompressorRelease => write!(f, "Cmp Rel"),
+            Parameter::CompressorMakeup => write!(f, "Makeup"),
+            Parameter::EqFrequency => write!(f, "Freq"),
+            Parameter::EqGain => write!(f, "Gain"),
+            Parameter::EqQ => write!(f, "Q")
+        }
+    }
+}
 
 // Synthesizer State Struct
 pub struct State {
     octave: i32,
-    waveform: Waveform,
+    waveform: usize,
+    second_waveform: usize,
+    second_oscillator: SecondOscillatorParameters,
+    sub_noise: SubNoiseParameters,
+    fm: FmParameters,
+    fm_operator: usize,
+    modulation: ModulationParameters,
+    lfo_index: usize,
+    modulation_slot: usize,
+    velocity: f32,
+    modulated_cutoff: f32,
+    unison: UnisonParameters,
+    stereo: StereoParameters,
+    effects: EffectsParameters,
+    effect: EffectKind,
+    gain_reduction: f32,
+    equalizer: EqParameters,
+    eq_band: usize,
     pressed_key: Option<(Key, Note)>,
-    waveform_sprite_index: usize,
-    filter_factor: f32,
-    lpf_active: usize
+    held_keys: Vec<(Key, Note, VoiceId)>,
+    legato: bool,
+    glide_time: f32,
+    pulse_width: f32,
+    wavetables: Vec<Arc<Wavetable>>,
+    wavetable_index: usize,
+    wavetable_position: f32,
+    pitch_bend: f32,
+    filter_cutoff: f32,
+    filter_resonance: f32,
+    filter_mode: FilterMode,
+    filter_envelope: AdsrParameters,
+    filter_envelope_amount: f32,
+    envelope: AdsrParameters,
+    envelope_stage: EnvelopeStage,
+    selected_parameter: Parameter
 }
 
 // Initialize Synthesizer State
 impl State {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(wavetables: Vec<Arc<Wavetable>>) -> Self {
         State {
             octave: 4, // Set default octave to 4
-            waveform: Waveform::SINE, // Set default waveform to Sine
+            waveform: 0, // Set default waveform to the first one registered, Sine
+            second_waveform: 0, // Set default waveform of the second oscillator to Sine as well
+            second_oscillator: SecondOscillatorParameters::default(), // The second oscillator is muted and in tune by default
+            sub_noise: SubNoiseParameters::default(), // The sub-oscillator is one octave down, and both it and the noise are muted by default
+            fm: FmParameters::default(), // Two operators with the second one modulating the first at the same frequency
+            fm_operator: 0, // Up/Down adjusts the first operator when an FM parameter is selected
+            modulation: ModulationParameters::default(), // Only velocity modulates the amplitude, the tempo is 120 BPM and the mod wheel is down
+            lfo_index: 0, // Up/Down adjusts the first LFO when an LFO parameter is selected
+            modulation_slot: 0, // Up/Down adjusts the first route of the modulation matrix when its amount is selected
+            velocity: 1.0, // Notes are struck at full velocity by default
+            modulated_cutoff: MAX_CUTOFF, // The cutoff is not modulated until the voice engine reports otherwise
+            unison: UnisonParameters::default(), // A single layer per voice, i.e. unison is off by default
+            stereo: StereoParameters::default(), // Voices are centered and the stereo field is left unchanged by default
+            effects: EffectsParameters::default(), // Distortion, bitcrusher, chorus, delay and reverb in this order, all bypassed by default
+            effect: EffectKind::Delay, // The delay is edited first when an effect parameter is selected
+            gain_reduction: 0.0, // The limiter does not reduce the gain until the voice engine reports otherwise
+            equalizer: EqParameters::default(), // All bands are flat and the equalizer is bypassed by default
+            eq_band: 0, // Up/Down adjusts the low shelf when an equalizer parameter is selected
             pressed_key: None, // Default is no key
-            waveform_sprite_index: WAVEFORM_SINE, // Set default waveform sprite index to Sine
-            filter_factor: 1.0, // Set default cutoff to 1.0
-            lpf_active: 0, // Default for LPF is deactivated
+            held_keys: Vec::new(), // No keys are held down initially
+            legato: false, // Every key starts a voice of its own by default
+            glide_time: 0.1, // Glide to a new note in legato mode over a tenth of a second by default
+            pulse_width: DEFAULT_PULSE_WIDTH, // Set default duty cycle of the pulse wave to 25%
+            wavetables, // Wavetables loaded from the assets folder
+            wavetable_index: 0, // Set default wavetable to the first one loaded
+            wavetable_position: 0.0, // Set default wavetable position to the first frame
+            pitch_bend: 0.0, // Pitch is not bent by default
+            filter_cutoff: MAX_CUTOFF, // Set default cutoff to fully open
+            filter_resonance: 0.0, // Set default resonance to none
+            filter_mode: FilterMode::Off, // Default for the filter is deactivated
+            filter_envelope: AdsrParameters::default(), // Same shape as the amplitude envelope by default
+            filter_envelope_amount: 0.0, // The filter envelope does not move the cutoff by default
+            envelope: AdsrParameters::default(), // Short attack with a moderate decay, sustain and release
+            envelope_stage: EnvelopeStage::Idle, // No voice is sounding initially
+            selected_parameter: Parameter::Attack, // Up/Down adjusts the attack time by default
         }
     }
 
-    /// Multiplies the sample frequency with that of the filter cutoff coefficient
-    pub fn apply_lpf(&mut self, sample: f32) -> f32 {
-        sample * self.filter_factor
-    }
-
     /// Increases the octave by one step, ensuring it does not exceed the upper bound.
     pub fn increase_octave(&mut self) {
         if self.octave < OCTAVE_UPPER_BOUND {
@@ -54,42 +254,790 @@ impl State {
         }
     }
 
-    /// Toggle LPF on/off
+    /// Toggle LPF on/off, switching off the filter regardless of its current mode
     pub fn toggle_lpf(&mut self) {
-        self.lpf_active ^= 1;
-        self.filter_factor = 1.0;
+        self.filter_mode = match self.filter_mode {
+            FilterMode::Off => FilterMode::LowPass,
+            _ => FilterMode::Off
+        };
+        self.filter_cutoff = MAX_CUTOFF;
     }
 
-    /// Increases the filter cutoff
+    /// Cycles the filter between low-pass, high-pass, band-pass and notch, switching it on if it was off
+    pub fn cycle_filter_mode(&mut self) {
+        self.filter_mode = self.filter_mode.next();
+    }
+
+    /// Increases the filter cutoff by one octave
     pub fn increase_filter_cutoff(&mut self) {
-        if self.lpf_active == 1 && self.filter_factor <= 0.9 {
-            self.filter_factor += 0.142857;
+        if self.filter_mode != FilterMode::Off && self.filter_cutoff < MAX_CUTOFF {
+            self.filter_cutoff = (self.filter_cutoff * CUTOFF_STEP).min(MAX_CUTOFF);
         }
     }
 
-    /// Decreases the filter cutoff
+    /// Decreases the filter cutoff by one octave
     pub fn decrease_filter_cutoff(&mut self) {
-        if self.lpf_active == 1 && self.filter_factor >= 0.15 {
-            self.filter_factor -= 0.142857;
+        if self.filter_mode != FilterMode::Off && self.filter_cutoff > MIN_CUTOFF {
+            self.filter_cutoff = (self.filter_cutoff / CUTOFF_STEP).max(MIN_CUTOFF);
         }
     }
 
-    /// Returns the current octave value.
-    pub fn get_current_octave(&self) -> i32 {
-        self.octave
+    /// Increases the filter resonance
+    pub fn increase_filter_resonance(&mut self) {
+        self.filter_resonance = (self.filter_resonance + RESONANCE_STEP).min(MAX_RESONANCE);
+    }
+
+    /// Decreases the filter resonance
+    pub fn decrease_filter_resonance(&mut self) {
+        self.filter_resonance = (self.filter_resonance - RESONANCE_STEP).max(0.0);
+    }
+
+    /// Returns the filter settings to be applied by the voice engine
+    pub fn filter_parameters(&self) -> FilterParameters {
+        FilterParameters {
+            mode: self.filter_mode,
+            cutoff: self.filter_cutoff,
+            resonance: self.filter_resonance,
+            envelope: self.filter_envelope,
+            envelope_amount: self.filter_envelope_amount
+        }
+    }
+
+    /// Registers a key which is held down along with the voice it started and marks it as the most recently pressed key.
+    pub fn hold_key(&mut self, key: Key, note: Note, voice: VoiceId) {
+        self.held_keys.push((key, note, voice));
+        self.pressed_key = Some((key, note));
+    }
+
+    /// Removes a key which is no longer held down, returning the voice it started.
+    /// The most recently pressed key falls back to the latest key still held, if any.
+    pub fn release_key(&mut self, key: Key) -> Option<VoiceId> {
+        let index = self.held_keys.iter().position(|(held, _, _)| *held == key)?;
+        let (_, _, voice) = self.held_keys.remove(index);
+        self.pressed_key = self.held_keys.last().map(|(key, note, _)| (*key, *note));
+        Some(voice)
+    }
+
+    /// Toggles the monophonic legato mode on/off and selects the glide time as the parameter adjusted by the Up/Down keys.
+    pub fn toggle_legato(&mut self) {
+        self.legato = !self.legato;
+        self.selected_parameter = Parameter::GlideTime;
+    }
+
+    /// Returns the voice a newly pressed key slides to its note, which is the voice of the most recently pressed key
+    /// still held in legato mode, or none if the key should start a voice of its own.
+    pub fn legato_voice(&self) -> Option<VoiceId> {
+        self.held_keys.last().filter(|_| self.legato).map(|(_, _, voice)| *voice)
+    }
+
+    /// Returns the note of the most recently pressed key still holding the given voice, if any,
+    /// which a voice slides back to when a later key gliding it is released.
+    pub fn held_note_of_voice(&self, voice: VoiceId) -> Option<Note> {
+        self.held_keys.iter().rev().find(|(_, _, held)| *held == voice).map(|(_, note, _)| *note)
+    }
+
+    /// Returns the time in seconds a voice takes to glide to a new note in legato mode.
+    pub fn glide_time(&self) -> f32 {
+        self.glide_time
+    }
+
+    /// Returns true if the given key is currently held down.
+    pub fn is_key_held(&self, key: Key) -> bool {
+        self.held_keys.iter().any(|(held, _, _)| *held == key)
+    }
+
+    /// Mirrors the envelope stage of the most recently started voice, as reported by the voice engine.
+    pub fn update_envelope_stage(&mut self, stage: EnvelopeStage) {
+        self.envelope_stage = stage;
+    }
+
+    /// Cycles the selected parameter through attack, decay, sustain and release of the envelope.
+    pub fn select_next_envelope_parameter(&mut self) {
+        self.selected_parameter = match self.selected_parameter {
+            Parameter::Attack => Parameter::Decay,
+            Parameter::Decay => Parameter::Sustain,
+            Parameter::Sustain => Parameter::Release,
+            _ => Parameter::Attack
+        };
+    }
+
+    /// Cycles the selected parameter through attack, decay, sustain and release of the filter envelope and its amount.
+    pub fn select_next_filter_envelope_parameter(&mut self) {
+        self.selected_parameter = match self.selected_parameter {
+            Parameter::FilterAttack => Parameter::FilterDecay,
+            Parameter::FilterDecay => Parameter::FilterSustain,
+            Parameter::FilterSustain => Parameter::FilterRelease,
+            Parameter::FilterRelease => Parameter::FilterEnvelopeAmount,
+            _ => Parameter::FilterAttack
+        };
+    }
+
+    /// Cycles the selected parameter through the mix, coarse detune and fine detune of the second oscillator.
+    pub fn select_next_oscillator_parameter(&mut self) {
+        self.selected_parameter = match self.selected_parameter {
+            Parameter::OscillatorMix => Parameter::CoarseDetune,
+            Parameter::CoarseDetune => Parameter::FineDetune,
+            _ => Parameter::OscillatorMix
+        };
+    }
+
+    /// Cycles the way the second oscillator is combined with the first one through mix, ring modulation and amplitude modulation.
+    pub fn cycle_oscillator_combiner(&mut self) {
+        self.second_oscillator.combiner = self.second_oscillator.combiner.next();
     }
 
-    /// Toggles the waveform between SINE and SQUARE and sets the associated sprite index accordingly.
-    pub fn toggle_waveform(&mut self) {
-        self.waveform = match self.waveform {
-            Waveform::SINE => {
-                self.waveform_sprite_index = WAVEFORM_SQUARE;
-                Waveform::SQUARE
+    /// Toggles hard sync of the second oscillator to the first one on/off.
+    pub fn toggle_oscillator_sync(&mut self) {
+        self.second_oscillator.sync = !self.second_oscillator.sync;
+    }
+
+    /// Returns the detune, mix and sync of the second oscillator.
+    pub fn second_oscillator_parameters(&self) -> SecondOscillatorParameters {
+        self.second_oscillator
+    }
+
+    /// Cycles the selected parameter through the level and octave of the sub-oscillator and the level of the noise generator.
+    pub fn select_next_sub_noise_parameter(&mut self) {
+        self.selected_parameter = match self.selected_parameter {
+            Parameter::SubLevel => Parameter::SubOctaves,
+            Parameter::SubOctaves => Parameter::NoiseLevel,
+            _ => Parameter::SubLevel
+        };
+    }
+
+    /// Returns the read position within the wavetables, in the range [0.0, 1.0].
+    pub fn wavetable_position(&self) -> f32 {
+        self.wavetable_position
+    }
+
+    /// Returns the algorithm and operators of the FM oscillator.
+    pub fn fm_parameters(&self) -> FmParameters {
+        self.fm
+    }
+
+    /// Returns the tuning and levels of the sub-oscillator and noise generator.
+    pub fn sub_noise_parameters(&self) -> SubNoiseParameters {
+        self.sub_noise
+    }
+
+    /// Selects the next FM operator for editing, wrapping around after the last one.
+    /// The ratio of the operator is selected unless a parameter of the previous operator was selected.
+    pub fn select_next_fm_operator(&mut self) {
+        self.fm_operator = (self.fm_operator + 1) % FM_OPERATORS;
+        if !matches!(self.selected_parameter, Parameter::FmRatio | Parameter::FmIndex | Parameter::FmFeedback) {
+            self.selected_parameter = Parameter::FmRatio;
+        }
+    }
+
+    /// Cycles the selected parameter through the ratio, modulation index and feedback of the selected FM operator.
+    pub fn select_next_fm_parameter(&mut self) {
+        self.selected_parameter = match self.selected_parameter {
+            Parameter::FmRatio => Parameter::FmIndex,
+            Parameter::FmIndex => Parameter::FmFeedback,
+            _ => Parameter::FmRatio
+        };
+    }
+
+    /// Selects the next FM algorithm, wrapping around after the last one.
+    pub fn cycle_fm_algorithm(&mut self) {
+        self.fm.algorithm = self.fm.algorithm.next();
+    }
+
+    /// Selects the next LFO for editing, wrapping around after the last one.
+    /// The rate of the LFO is selected as the parameter adjusted by the Up/Down keys.
+    pub fn select_next_lfo(&mut self) {
+        self.lfo_index = (self.lfo_index + 1) % LFO_COUNT;
+        self.selected_parameter = Parameter::LfoRate;
+    }
+
+    /// Toggles the selected parameter between the rate of the selected LFO and the tempo.
+    pub fn select_next_lfo_parameter(&mut self) {
+        self.selected_parameter = match self.selected_parameter {
+            Parameter::LfoRate => Parameter::Tempo,
+            _ => Parameter::LfoRate
+        };
+    }
+
+    /// Cycles the shape of the selected LFO.
+    pub fn cycle_lfo_shape(&mut self) {
+        let lfo = &mut self.modulation.lfos[self.lfo_index];
+        lfo.shape = lfo.shape.next();
+    }
+
+    /// Selects the next route of the modulation matrix for editing, wrapping around after the last one,
+    /// along with its amount as the parameter adjusted by the Up/Down keys.
+    pub fn select_next_modulation_slot(&mut self) {
+        if self.selected_parameter == Parameter::ModulationAmount {
+            self.modulation_slot = (self.modulation_slot + 1) % MODULATION_SLOTS;
+        }
+        self.selected_parameter = Parameter::ModulationAmount;
+    }
+
+    /// Cycles the source of the selected route of the modulation matrix.
+    pub fn cycle_modulation_source(&mut self) {
+        let slot = &mut self.modulation.slots[self.modulation_slot];
+        slot.source = slot.source.next();
+        self.selected_parameter = Parameter::ModulationAmount;
+    }
+
+    /// Cycles the destination of the selected route of the modulation matrix.
+    pub fn cycle_modulation_destination(&mut self) {
+        let slot = &mut self.modulation.slots[self.modulation_slot];
+        slot.destination = slot.destination.next();
+        self.selected_parameter = Parameter::ModulationAmount;
+    }
+
+    /// Returns the routes of the modulation matrix along with the index of the one selected for editing.
+    pub fn modulation_slots(&self) -> (&[ModulationSlot], usize) {
+        (&self.modulation.slots, self.modulation_slot)
+    }
+
+    /// Moves the mod wheel up or down by one step for a positive or negative direction.
+    pub fn move_mod_wheel(&mut self, direction: f32) {
+        self.modulation.mod_wheel = (self.modulation.mod_wheel + MOD_WHEEL_STEP * direction.signum()).clamp(0.0, 1.0);
+    }
+
+    /// Increases or decreases the velocity new notes are struck with by one step for a positive or negative direction.
+    pub fn change_velocity(&mut self, direction: f32) {
+        self.velocity = (self.velocity + VELOCITY_STEP * direction.signum()).clamp(0.0, 1.0);
+    }
+
+    /// Returns the velocity new notes are struck with, in the range [0.0, 1.0].
+    pub fn velocity(&self) -> f32 {
+        self.velocity
+    }
+
+    /// Toggles whether the rate of the selected LFO follows the tempo, starting at a period of a quarter note.
+    pub fn toggle_lfo_sync(&mut self) {
+        let lfo = &mut self.modulation.lfos[self.lfo_index];
+        lfo.division = match lfo.division {
+            Some(_) => None,
+            None => LFO_DIVISIONS.iter().position(|(name, _)| *name == "1/4")
+        };
+    }
+
+    /// Returns the settings of the LFOs and the tempo.
+    pub fn modulation_parameters(&self) -> ModulationParameters {
+        self.modulation
+    }
+
+    /// Mirrors the modulated filter cutoff, as reported by the voice engine, so the cutoff knob follows the modulation.
+    pub fn update_modulated_cutoff(&mut self, cutoff: f32) {
+        self.modulated_cutoff = cutoff;
+    }
+
+    /// Cycles the selected parameter through the number of layers, detune and stereo spread of the unison stack.
+    pub fn select_next_unison_parameter(&mut self) {
+        self.selected_parameter = match self.selected_parameter {
+            Parameter::UnisonVoices => Parameter::UnisonDetune,
+            Parameter::UnisonDetune => Parameter::UnisonSpread,
+            _ => Parameter::UnisonVoices
+        };
+    }
+
+    /// Returns the number of layers, detune and stereo spread of the unison stack.
+    pub fn unison_parameters(&self) -> UnisonParameters {
+        self.unison
+    }
+
+    /// Cycles the selected parameter through the pan of new voices and the stereo width of the master bus.
+    pub fn select_next_stereo_parameter(&mut self) {
+        self.selected_parameter = match self.selected_parameter {
+            Parameter::Pan => Parameter::StereoWidth,
+            _ => Parameter::Pan
+        };
+    }
+
+    /// Returns the pan of new voices and the stereo width of the master bus.
+    pub fn stereo_parameters(&self) -> StereoParameters {
+        self.stereo
+    }
+
+    /// Selects the next effect of the master effects chain for editing, along with its mix as the parameter
+    /// adjusted by the Up/Down keys.
+    pub fn select_next_effect(&mut self) {
+        self.effect = self.effect.next();
+        self.selected_parameter = Parameter::EffectMix;
+    }
+
+    /// Cycles the selected parameter through the mix and the settings of the selected effect.
+    pub fn select_next_effect_parameter(&mut self) {
+        self.selected_parameter = match (self.effect, self.selected_parameter) {
+            (EffectKind::Delay, Parameter::EffectMix) => Parameter::DelayTime,
+            (EffectKind::Delay, Parameter::DelayTime) => Parameter::DelayFeedback,
+            (EffectKind::Reverb, Parameter::EffectMix) => Parameter::ReverbSize,
+            (EffectKind::Reverb, Parameter::ReverbSize) => Parameter::ReverbDamping,
+            (EffectKind::Chorus, Parameter::EffectMix) => Parameter::ChorusRate,
+            (EffectKind::Chorus, Parameter::ChorusRate) => Parameter::ChorusDepth,
+            (EffectKind::Distortion, Parameter::EffectMix) => Parameter::DistortionDrive,
+            (EffectKind::Bitcrusher, Parameter::EffectMix) => Parameter::CrusherBits,
+            (EffectKind::Bitcrusher, Parameter::CrusherBits) => Parameter::CrusherDownsample,
+            (EffectKind::Compressor, Parameter::EffectMix) => Parameter::CompressorThreshold,
+            (EffectKind::Compressor, Parameter::CompressorThreshold) => Parameter::CompressorRatio,
+            (EffectKind::Compressor, Parameter::CompressorRatio) => Parameter::CompressorAttack,
+            (EffectKind::Compressor, Parameter::CompressorAttack) => Parameter::CompressorRelease,
+            (EffectKind::Compressor, Parameter::CompressorRelease) => Parameter::CompressorMakeup,
+            _ => Parameter::EffectMix
+        };
+    }
+
+    /// Bypasses the selected effect if it is enabled and enables it otherwise.
+    pub fn toggle_effect(&mut self) {
+        self.effects.toggle(self.effect);
+    }
+
+    /// Moves the selected effect one position later in the chain, moving the last effect to the front.
+    /// The compressor always follows the chain and is not moved.
+    pub fn move_effect_later(&mut self) {
+        if let Some(effect) = ChainEffect::of(self.effect) {
+            self.effects.move_later(effect);
+        }
+    }
+
+    /// Toggles the mode of the selected effect, which is the tempo sync of the delay, starting at an eighth note,
+    /// switching the chorus between chorus and flanger, cycling the shape of the distortion and the sidechain mode
+    /// of the compressor. The reverb and bitcrusher have no modes.
+    pub fn toggle_effect_mode(&mut self) {
+        match self.effect {
+            EffectKind::Delay => {
+                let delay = &mut self.effects.delay;
+                delay.division = match delay.division {
+                    Some(_) => None,
+                    None => LFO_DIVISIONS.iter().position(|(name, _)| *name == "1/8")
+                };
+            }
+            EffectKind::Chorus => self.effects.chorus.flanger = !self.effects.chorus.flanger,
+            EffectKind::Distortion => self.effects.distortion.shape = self.effects.distortion.shape.next(),
+            EffectKind::Compressor => self.effects.compressor.sidechain = self.effects.compressor.sidechain.next(),
+            EffectKind::Reverb | EffectKind::Bitcrusher => {}
+        }
+    }
+
+    /// Returns the order, bypass and settings of the master effects chain.
+    pub fn effects_parameters(&self) -> EffectsParameters {
+        self.effects
+    }
+
+    /// Selects the next band of the equalizer for editing, wrapping around after the last one.
+    /// The gain of the band is selected unless a parameter of the previous band was selected.
+    pub fn select_next_eq_band(&mut self) {
+        self.eq_band = (self.eq_band + 1) % EQ_BANDS;
+        if !matches!(self.selected_parameter, Parameter::EqFrequency | Parameter::EqGain | Parameter::EqQ) {
+            self.selected_parameter = Parameter::EqGain;
+        }
+    }
+
+    /// Cycles the selected parameter through the frequency, gain and quality factor of the selected equalizer band.
+    pub fn select_next_eq_parameter(&mut self) {
+        self.selected_parameter = match self.selected_parameter {
+            Parameter::EqFrequency => Parameter::EqGain,
+            Parameter::EqGain => Parameter::EqQ,
+            _ => Parameter::EqFrequency
+        };
+    }
+
+    /// Toggles the equalizer on the master output on/off.
+    pub fn toggle_equalizer(&mut self) {
+        self.equalizer.enabled = !self.equalizer.enabled;
+    }
+
+    /// Returns the bypass and the bands of the equalizer on the master output.
+    pub fn equalizer_parameters(&self) -> EqParameters {
+        self.equalizer
+    }
+
+    /// Mirrors the gain reduction of the master limiter in decibels, as reported by the voice engine.
+    pub fn update_gain_reduction(&mut self, gain_reduction: f32) {
+        self.gain_reduction = gain_reduction;
+    }
+
+    /// Returns the gain reduction of the master limiter in decibels.
+    pub fn gain_reduction(&self) -> f32 {
+        self.gain_reduction
+    }
+
+    /// Selects the wavetable position as the parameter adjusted by the Up/Down keys.
+    pub fn select_wavetable_position_parameter(&mut self) {
+        self.selected_parameter = Parameter::WavetablePosition;
+    }
+
+    /// Selects the next of the loaded wavetables, wrapping around after the last one.
+    pub fn cycle_wavetable(&mut self) {
+        if !self.wavetables.is_empty() {
+            self.wavetable_index = (self.wavetable_index + 1) % self.wavetables.len();
+        }
+    }
+
+    /// Returns the selected wavetable, or none if no wavetables were loaded.
+    pub fn current_wavetable(&self) -> Option<&Arc<Wavetable>> {
+        self.wavetables.get(self.wavetable_index)
+    }
+
+    /// Selects the duty cycle of the pulse wave as the parameter adjusted by the Up/Down keys.
+    pub fn select_pulse_width_parameter(&mut self) {
+        self.selected_parameter = Parameter::PulseWidth;
+    }
+
+    /// Increases the value of the selected parameter.
+    pub fn increase_selected_parameter(&mut self) {
+        self.adjust_selected_parameter(ENVELOPE_TIME_FACTOR, ENVELOPE_SUSTAIN_STEP);
+    }
+
+    /// Decreases the value of the selected parameter.
+    pub fn decrease_selected_parameter(&mut self) {
+        self.adjust_selected_parameter(1.0 / ENVELOPE_TIME_FACTOR, -ENVELOPE_SUSTAIN_STEP);
+    }
+
+    /// Scales envelope times by `time_factor` or offsets levels by `level_step`, depending on the selected parameter.
+    /// Envelope values are kept within bounds by [AdsrParameters::new].
+    fn adjust_selected_parameter(&mut self, time_factor: f32, level_step: f32) {
+        let AdsrParameters { attack, decay, sustain, release } = self.envelope;
+        let AdsrParameters { attack: filter_attack, decay: filter_decay, sustain: filter_sustain, release: filter_release } = self.filter_envelope;
+
+        match self.selected_parameter {
+            Parameter::Attack => self.envelope = AdsrParameters::new(attack * time_factor, decay, sustain, release),
+            Parameter::Decay => self.envelope = AdsrParameters::new(attack, decay * time_factor, sustain, release),
+            Parameter::Sustain => self.envelope = AdsrParameters::new(attack, decay, sustain + level_step, release),
+            Parameter::Release => self.envelope = AdsrParameters::new(attack, decay, sustain, release * time_factor),
+            Parameter::FilterAttack => self.filter_envelope = AdsrParameters::new(filter_attack * time_factor, filter_decay, filter_sustain, filter_release),
+            Parameter::FilterDecay => self.filter_envelope = AdsrParameters::new(filter_attack, filter_decay * time_factor, filter_sustain, filter_release),
+            Parameter::FilterSustain => self.filter_envelope = AdsrParameters::new(filter_attack, filter_decay, filter_sustain + level_step, filter_release),
+            Parameter::FilterRelease => self.filter_envelope = AdsrParameters::new(filter_attack, filter_decay, filter_sustain, filter_release * time_factor),
+            Parameter::FilterEnvelopeAmount => {
+                // Round to whole steps, so that the amount returns to exactly zero
+                let steps = (self.filter_envelope_amount / FILTER_ENVELOPE_AMOUNT_STEP).round() + level_step.signum();
+                self.filter_envelope_amount = (steps * FILTER_ENVELOPE_AMOUNT_STEP).clamp(-1.0, 1.0);
+            }
+            Parameter::PulseWidth => {
+                let step = PULSE_WIDTH_STEP * level_step.signum();
+                self.pulse_width = (self.pulse_width + step).clamp(MIN_PULSE_WIDTH, MAX_PULSE_WIDTH);
+            }
+            Parameter::WavetablePosition => {
+                let step = WAVETABLE_POSITION_STEP * level_step.signum();
+                self.wavetable_position = (self.wavetable_position + step).clamp(0.0, 1.0);
+            }
+            Parameter::OscillatorMix => {
+                let step = OSCILLATOR_MIX_STEP * level_step.signum();
+                self.second_oscillator.mix = (self.second_oscillator.mix + step).clamp(0.0, 1.0);
+            }
+            Parameter::CoarseDetune => {
+                let step = level_step.signum() as i32;
+                self.second_oscillator.coarse = (self.second_oscillator.coarse + step).clamp(-MAX_COARSE_DETUNE, MAX_COARSE_DETUNE);
+            }
+            Parameter::FineDetune => {
+                let step = level_step.signum() as i32;
+                self.second_oscillator.fine = (self.second_oscillator.fine + step).clamp(-MAX_FINE_DETUNE, MAX_FINE_DETUNE);
+            }
+            Parameter::SubLevel => {
+                let step = SUB_NOISE_LEVEL_STEP * level_step.signum();
+                self.sub_noise.sub_level = (self.sub_noise.sub_level + step).clamp(0.0, 1.0);
+            }
+            Parameter::SubOctaves => {
+                let octaves = self.sub_noise.sub_octaves as i32 + level_step.signum() as i32;
+                self.sub_noise.sub_octaves = octaves.clamp(1, MAX_SUB_OCTAVES as i32) as u32;
+            }
+            Parameter::NoiseLevel => {
+                let step = SUB_NOISE_LEVEL_STEP * level_step.signum();
+                self.sub_noise.noise_level = (self.sub_noise.noise_level + step).clamp(0.0, 1.0);
+            }
+            Parameter::FmRatio => {
+                let operator = &mut self.fm.operators[self.fm_operator];
+                operator.ratio = (operator.ratio + FM_RATIO_STEP * level_step.signum()).clamp(MIN_FM_RATIO, MAX_FM_RATIO);
+            }
+            Parameter::FmIndex => {
+                let operator = &mut self.fm.operators[self.fm_operator];
+                operator.index = (operator.index + FM_INDEX_STEP * level_step.signum()).clamp(0.0, MAX_FM_INDEX);
+            }
+            Parameter::FmFeedback => {
+                let operator = &mut self.fm.operators[self.fm_operator];
+                operator.feedback = (operator.feedback + FM_FEEDBACK_STEP * level_step.signum()).clamp(0.0, MAX_FM_FEEDBACK);
+            }
+            Parameter::LfoRate => {
+                let lfo = &mut self.modulation.lfos[self.lfo_index];
+                match lfo.division {
+                    // A higher division index is a shorter note and thereby a faster rate
+                    Some(division) => {
+                        let division = division as i32 + level_step.signum() as i32;
+                        lfo.division = Some(division.clamp(0, LFO_DIVISIONS.len() as i32 - 1) as usize);
+                    }
+                    None => lfo.rate = (lfo.rate * time_factor).clamp(MIN_LFO_RATE, MAX_LFO_RATE)
+                }
+            }
+            Parameter::Tempo => {
+                self.modulation.tempo = (self.modulation.tempo + TEMPO_STEP * level_step.signum()).clamp(MIN_TEMPO, MAX_TEMPO);
+            }
+            Parameter::ModulationAmount => {
+                // Round to whole steps, so that the amount returns to exactly zero and the route becomes inactive
+                let slot = &mut self.modulation.slots[self.modulation_slot];
+                let steps = (slot.amount / MODULATION_AMOUNT_STEP).round() + level_step.signum();
+                slot.amount = (steps * MODULATION_AMOUNT_STEP).clamp(-1.0, 1.0);
+            }
+            Parameter::GlideTime => self.glide_time = (self.glide_time * time_factor).clamp(MIN_GLIDE_TIME, MAX_GLIDE_TIME),
+            Parameter::UnisonVoices => {
+                let voices = self.unison.voices as i32 + level_step.signum() as i32;
+                self.unison.voices = voices.clamp(1, MAX_UNISON_VOICES as i32) as usize;
+            }
+            Parameter::UnisonDetune => {
+                let step = UNISON_DETUNE_STEP * level_step.signum();
+                self.unison.detune = (self.unison.detune + step).clamp(0.0, MAX_UNISON_DETUNE);
+            }
+            Parameter::UnisonSpread => {
+                let step = UNISON_SPREAD_STEP * level_step.signum();
+                self.unison.spread = (self.unison.spread + step).clamp(0.0, 1.0);
+            }
+            Parameter::Pan => {
+                let step = PAN_STEP * level_step.signum();
+                self.stereo.pan = (self.stereo.pan + step).clamp(-1.0, 1.0);
+            }
+            Parameter::StereoWidth => {
+                let step = STEREO_WIDTH_STEP * level_step.signum();
+                self.stereo.width = (self.stereo.width + step).clamp(0.0, MAX_STEREO_WIDTH);
+            }
+            Parameter::EffectMix => {
+                let mix = self.effects.mix_mut(self.effect);
+                *mix = (*mix + EFFECT_LEVEL_STEP * level_step.signum()).clamp(0.0, 1.0);
+            }
+            Parameter::DelayTime => {
+                let delay = &mut self.effects.delay;
+                match delay.division {
+                    // A higher division index is a shorter note and thereby a shorter delay
+                    Some(division) => {
+                        let division = division as i32 - level_step.signum() as i32;
+                        delay.division = Some(division.clamp(0, LFO_DIVISIONS.len() as i32 - 1) as usize);
+                    }
+                    None => delay.time = (delay.time * time_factor).clamp(MIN_DELAY_TIME, MAX_DELAY_TIME)
+                }
+            }
+            Parameter::DelayFeedback => {
+                let step = EFFECT_LEVEL_STEP * level_step.signum();
+                self.effects.delay.feedback = (self.effects.delay.feedback + step).clamp(0.0, MAX_DELAY_FEEDBACK);
+            }
+            Parameter::ReverbSize => {
+                let step = EFFECT_LEVEL_STEP * level_step.signum();
+                self.effects.reverb.size = (self.effects.reverb.size + step).clamp(0.0, 1.0);
+            }
+            Parameter::ReverbDamping => {
+                let step = EFFECT_LEVEL_STEP * level_step.signum();
+                self.effects.reverb.damping = (self.effects.reverb.damping + step).clamp(0.0, 1.0);
+            }
+            Parameter::ChorusRate => {
+                self.effects.chorus.rate = (self.effects.chorus.rate * time_factor).clamp(MIN_CHORUS_RATE, MAX_CHORUS_RATE);
+            }
+            Parameter::ChorusDepth => {
+                let step = EFFECT_LEVEL_STEP * level_step.signum();
+                self.effects.chorus.depth = (self.effects.chorus.depth + step).clamp(0.0, 1.0);
+            }
+            Parameter::DistortionDrive => {
+                let step = DISTORTION_DRIVE_STEP * level_step.signum();
+                self.effects.distortion.drive = (self.effects.distortion.drive + step).clamp(0.0, MAX_DISTORTION_DRIVE);
+            }
+            Parameter::CrusherBits => {
+                let bits = self.effects.bitcrusher.bits as i32 + level_step.signum() as i32;
+                self.effects.bitcrusher.bits = bits.clamp(MIN_CRUSHER_BITS as i32, MAX_CRUSHER_BITS as i32) as u32;
+            }
+            Parameter::CrusherDownsample => {
+                // A higher rate is a smaller factor the sample rate is divided by
+                let downsample = self.effects.bitcrusher.downsample as i32 - level_step.signum() as i32;
+                self.effects.bitcrusher.downsample = downsample.clamp(1, MAX_CRUSHER_DOWNSAMPLE as i32) as u32;
+            }
+            Parameter::CompressorThreshold => {
+                let compressor = &mut self.effects.compressor;
+                compressor.threshold = (compressor.threshold + COMPRESSOR_LEVEL_STEP * level_step.signum()).clamp(MIN_COMPRESSOR_THRESHOLD, 0.0);
+            }
+            Parameter::CompressorRatio => {
+                let compressor = &mut self.effects.compressor;
+                compressor.ratio = (compressor.ratio * time_factor).clamp(1.0, MAX_COMPRESSOR_RATIO);
+            }
+            Parameter::CompressorAttack => {
+                let compressor = &mut self.effects.compressor;
+                compressor.attack = (compressor.attack * time_factor).clamp(MIN_COMPRESSOR_TIME, MAX_COMPRESSOR_TIME);
+            }
+            Parameter::CompressorRelease => {
+                let compressor = &mut self.effects.compressor;
+                compressor.release = (compressor.release * time_factor).clamp(MIN_COMPRESSOR_TIME, MAX_COMPRESSOR_TIME);
+            }
+            Parameter::CompressorMakeup => {
+                let compressor = &mut self.effects.compressor;
+                compressor.makeup = (compressor.makeup + COMPRESSOR_LEVEL_STEP * level_step.signum()).clamp(0.0, MAX_COMPRESSOR_MAKEUP);
+            }
+            Parameter::EqFrequency => {
+                let band = &mut self.equalizer.bands[self.eq_band];
+                band.frequency = (band.frequency * time_factor).clamp(MIN_EQ_FREQUENCY, MAX_EQ_FREQUENCY);
+            }
+            Parameter::EqGain => {
+                let band = &mut self.equalizer.bands[self.eq_band];
+                band.gain = (band.gain + EQ_GAIN_STEP * level_step.signum()).clamp(-MAX_EQ_GAIN, MAX_EQ_GAIN);
+            }
+            Parameter::EqQ => {
+                let band = &mut self.equalizer.bands[self.eq_band];
+                band.q = (band.q * time_factor).clamp(MIN_EQ_Q, MAX_EQ_Q);
+            }
+        }
+    }
+
+    /// Returns a short description of the selected parameter and its current value, e.g. "Attack 0.010s".
+    pub fn describe_selected_parameter(&self) -> String {
+        match self.selected_parameter {
+            Parameter::Attack => format!("{} {:.3}s", self.selected_parameter, self.envelope.attack),
+            Parameter::Decay => format!("{} {:.3}s", self.selected_parameter, self.envelope.decay),
+            Parameter::Sustain => format!("{} {:.2}", self.selected_parameter, self.envelope.sustain),
+            Parameter::Release => format!("{} {:.3}s", self.selected_parameter, self.envelope.release),
+            Parameter::FilterAttack => format!("{} {:.3}s", self.selected_parameter, self.filter_envelope.attack),
+            Parameter::FilterDecay => format!("{} {:.3}s", self.selected_parameter, self.filter_envelope.decay),
+            Parameter::FilterSustain => format!("{} {:.2}", self.selected_parameter, self.filter_envelope.sustain),
+            Parameter::FilterRelease => format!("{} {:.3}s", self.selected_parameter, self.filter_envelope.release),
+            Parameter::FilterEnvelopeAmount => format!("{} {:+.0}%", self.selected_parameter, self.filter_envelope_amount * 100.0),
+            Parameter::PulseWidth => format!("{} {:.0}%", self.selected_parameter, self.pulse_width * 100.0),
+            Parameter::WavetablePosition => format!("{} {:.0}%", self.selected_parameter, self.wavetable_position * 100.0),
+            Parameter::OscillatorMix => format!("{} {:.0}%", self.selected_parameter, self.second_oscillator.mix * 100.0),
+            Parameter::CoarseDetune => format!("{} {:+}st", self.selected_parameter, self.second_oscillator.coarse),
+            Parameter::FineDetune => format!("{} {:+}ct", self.selected_parameter, self.second_oscillator.fine),
+            Parameter::SubLevel => format!("{} {:.0}%", self.selected_parameter, self.sub_noise.sub_level * 100.0),
+            Parameter::SubOctaves => format!("{} -{}", self.selected_parameter, self.sub_noise.sub_octaves),
+            Parameter::NoiseLevel => format!("{} {:.0}%", self.selected_parameter, self.sub_noise.noise_level * 100.0),
+            Parameter::FmRatio => format!("Op{} {} {:.1}", self.fm_operator + 1, self.selected_parameter, self.fm.operators[self.fm_operator].ratio),
+            Parameter::FmIndex => format!("Op{} {} {:.2}", self.fm_operator + 1, self.selected_parameter, self.fm.operators[self.fm_operator].index),
+            Parameter::FmFeedback => format!("Op{} {} {:.0}%", self.fm_operator + 1, self.selected_parameter, self.fm.operators[self.fm_operator].feedback * 100.0),
+            Parameter::LfoRate => {
+                let lfo = &self.modulation.lfos[self.lfo_index];
+                match lfo.division {
+                    Some(division) => format!("LFO{} {} {} {}", self.lfo_index + 1, lfo.shape, self.selected_parameter, LFO_DIVISIONS[division].0),
+                    None => format!("LFO{} {} {} {:.2}Hz", self.lfo_index + 1, lfo.shape, self.selected_parameter, lfo.rate)
+                }
+            }
+            Parameter::Tempo => format!("{} {:.0}bpm", self.selected_parameter, self.modulation.tempo),
+            Parameter::ModulationAmount => {
+                let slot = &self.modulation.slots[self.modulation_slot];
+                format!("M{} {}>{} {:+.0}%", self.modulation_slot + 1, slot.source, slot.destination, slot.amount * 100.0)
+            }
+            Parameter::GlideTime => {
+                let mode = if self.legato { "Mono" } else { "Poly" };
+                format!("{} {} {:.3}s", mode, self.selected_parameter, self.glide_time)
+            }
+            Parameter::UnisonVoices => format!("{} x{}", self.selected_parameter, self.unison.voices),
+            Parameter::UnisonDetune => format!("{} {:.0}ct", self.selected_parameter, self.unison.detune),
+            Parameter::UnisonSpread => format!("{} {:.0}%", self.selected_parameter, self.unison.spread * 100.0),
+            Parameter::Pan => match (self.stereo.pan * 100.0).round() as i32 {
+                0 => format!("{} C", self.selected_parameter),
+                pan if pan < 0 => format!("{} L{}", self.selected_parameter, -pan),
+                pan => format!("{} R{}", self.selected_parameter, pan)
             },
-            Waveform::SQUARE => {
-                self.waveform_sprite_index = WAVEFORM_SINE;
-                Waveform::SINE
+            Parameter::StereoWidth => format!("{} {:.0}%", self.selected_parameter, self.stereo.width * 100.0),
+            Parameter::EffectMix => {
+                let mix = match self.effect {
+                    EffectKind::Delay => self.effects.delay.mix,
+                    EffectKind::Reverb => self.effects.reverb.mix,
+                    EffectKind::Chorus => self.effects.chorus.mix,
+                    EffectKind::Distortion => self.effects.distortion.mix,
+                    EffectKind::Bitcrusher => self.effects.bitcrusher.mix,
+                    EffectKind::Compressor => self.effects.compressor.mix
+                };
+                format!("{} {} {:.0}%", self.effect_label(self.effect), self.selected_parameter, mix * 100.0)
+            }
+            Parameter::DelayTime => match self.effects.delay.division {
+                Some(division) => format!("{} {} {}", self.effect_label(self.effect), self.selected_parameter, LFO_DIVISIONS[division].0),
+                None => format!("{} {} {:.3}s", self.effect_label(self.effect), self.selected_parameter, self.effects.delay.time)
             },
+            Parameter::DelayFeedback => format!("{} {} {:.0}%", self.effect_label(self.effect), self.selected_parameter, self.effects.delay.feedback * 100.0),
+            Parameter::ReverbSize => format!("{} {} {:.0}%", self.effect_label(self.effect), self.selected_parameter, self.effects.reverb.size * 100.0),
+            Parameter::ReverbDamping => format!("{} {} {:.0}%", self.effect_label(self.effect), self.selected_parameter, self.effects.reverb.damping * 100.0),
+            Parameter::ChorusRate => format!("{} {} {:.2}Hz", self.effect_label(self.effect), self.selected_parameter, self.effects.chorus.rate),
+            Parameter::ChorusDepth => format!("{} {} {:.0}%", self.effect_label(self.effect), self.selected_parameter, self.effects.chorus.depth * 100.0),
+            Parameter::DistortionDrive => format!("{} {} {:.1}dB", self.effect_label(self.effect), self.selected_parameter, self.effects.distortion.drive),
+            Parameter::CrusherBits => format!("{} {} {}", self.effect_label(self.effect), self.selected_parameter, self.effects.bitcrusher.bits),
+            Parameter::CrusherDownsample => format!("{} {} 1/{}", self.effect_label(self.effect), self.selected_parameter, self.effects.bitcrusher.downsample),
+            Parameter::CompressorThreshold => format!("{} {} {:.0}dB", self.effect_label(self.effect), self.selected_parameter, self.effects.compressor.threshold),
+            Parameter::CompressorRatio => format!("{} {} {:.1}:1", self.effect_label(self.effect), self.selected_parameter, self.effects.compressor.ratio),
+            Parameter::CompressorAttack => format!("{} {} {:.3}s", self.effect_label(self.effect), self.selected_parameter, self.effects.compressor.attack),
+            Parameter::CompressorRelease => format!("{} {} {:.3}s", self.effect_label(self.effect), self.selected_parameter, self.effects.compressor.release),
+            Parameter::CompressorMakeup => format!("{} {} {:+.0}dB", self.effect_label(self.effect), self.selected_parameter, self.effects.compressor.makeup),
+            Parameter::EqFrequency => format!("{} {} {:.0}Hz", self.eq_band_label(), self.selected_parameter, self.equalizer.bands[self.eq_band].frequency),
+            Parameter::EqGain => format!("{} {} {:+.1}dB", self.eq_band_label(), self.selected_parameter, self.equalizer.bands[self.eq_band].gain),
+            Parameter::EqQ => format!("{} {} {:.2}", self.eq_band_label(), self.selected_parameter, self.equalizer.bands[self.eq_band].q)
+        }
+    }
+
+    /// Returns the name of the selected equalizer band along with its response, e.g. "EQ1 LS",
+    /// which is lower case while the equalizer is bypassed.
+    fn eq_band_label(&self) -> String {
+        let label = format!("EQ{} {}", self.eq_band + 1, Equalizer::band_response(self.eq_band));
+        if self.equalizer.enabled { label } else { label.to_lowercase() }
+    }
+
+    /// Returns the name shown for an effect, which reads "FLG" for the chorus in flanger mode, names the shape
+    /// of the distortion, reads "SCN" or "SCT" for the compressor keyed by the notes or the tempo, and is lower case
+    /// while the effect is bypassed.
+    pub fn effect_label(&self, kind: EffectKind) -> String {
+        let label = match kind {
+            EffectKind::Chorus if self.effects.chorus.flanger => "FLG".to_string(),
+            EffectKind::Distortion => self.effects.distortion.shape.to_string(),
+            EffectKind::Compressor if self.effects.compressor.sidechain == SidechainMode::Notes => "SCN".to_string(),
+            EffectKind::Compressor if self.effects.compressor.sidechain == SidechainMode::Tempo => "SCT".to_string(),
+            kind => kind.to_string()
         };
+
+        if self.effects.is_enabled(kind) { label } else { label.to_lowercase() }
+    }
+
+    /// Sets the pitch bend from the direction keys held, where -1.0 bends fully down and 1.0 fully up.
+    pub fn set_pitch_bend(&mut self, direction: f32) {
+        self.pitch_bend = direction.clamp(-1.0, 1.0);
+    }
+
+    /// Returns the pitch bend in semitones.
+    pub fn pitch_bend_semitones(&self) -> f32 {
+        self.pitch_bend * PITCH_BEND_RANGE
+    }
+
+    /// Returns the current octave value.
+    pub fn get_current_octave(&self) -> i32 {
+        self.octave
+    }
+
+    /// Cycles the first oscillator through the registered waveforms, wrapping around after the last one.
+    pub fn cycle_waveform(&mut self) {
+        self.waveform = self.next_waveform(self.waveform);
+    }
+
+    /// Cycles the second oscillator through the registered waveforms, wrapping around after the last one.
+    pub fn cycle_second_waveform(&mut self) {
+        self.second_waveform = self.next_waveform(self.second_waveform);
+    }
+
+    /// Returns the index of the registered waveform following the given one.
+    /// Waveforms requiring a wavetable are skipped if no wavetables were loaded.
+    fn next_waveform(&self, mut waveform: usize) -> usize {
+        loop {
+            waveform = (waveform + 1) % WAVEFORMS.len();
+            if !WAVEFORMS[waveform].requires_wavetable || !self.wavetables.is_empty() {
+                return waveform;
+            }
+        }
+    }
+
+    /// Returns the definition of the waveform selected for the first oscillator.
+    pub fn waveform(&self) -> &'static WaveformDefinition {
+        &WAVEFORMS[self.waveform]
+    }
+
+    /// Returns the definition of the waveform selected for the second oscillator.
+    pub fn second_waveform(&self) -> &'static WaveformDefinition {
+        &WAVEFORMS[self.second_waveform]
+    }
+
+    /// Returns the settings from which an oscillator playing the selected waveform is created.
+    ///
+    /// # Parameters
+    /// - `frequency`: The frequency of the note to be played in Hz.
+    /// - `seed`: The seed of noise generators, which should differ between notes.
+    pub fn oscillator_settings(&self, frequency: f32, seed: u32) -> OscillatorSettings<'_> {
+        OscillatorSettings {
+            frequency,
+            pulse_width: self.pulse_width,
+            wavetable: self.current_wavetable(),
+            wavetable_position: self.wavetable_position,
+            fm: self.fm,
+            seed
+        }
     }
 }
\ No newline at end of file
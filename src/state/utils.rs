@@ -1,45 +1,202 @@
 use std::collections::HashMap;
-use std::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use minifb::{Key, KeyRepeat, Window};
-use rodio::{Sink, Source};
 
 use crate::{
     graphics::constants::*
 };
+use crate::effects::EffectKind;
+use crate::engine::Engine;
+use crate::engine::voice::{SubNoiseSources, VoiceId};
+use crate::filters::{FilterMode, MAX_RESONANCE, MIN_CUTOFF};
 use crate::graphics::sprites::{draw_sprite, Sprite, Sprites};
 use crate::music_theory::{OCTAVE_LOWER_BOUND, OCTAVE_UPPER_BOUND};
 use crate::music_theory::note::Note;
 use crate::state::State;
-use crate::waveforms::{AMPLITUDE, DURATION, Waveform};
-use crate::waveforms::sine_wave::SineWave;
+use crate::waveforms::noise::WhiteNoise;
+use crate::waveforms::registry::{OscillatorSettings, WaveformDefinition, WaveformDisplay};
 use crate::waveforms::square_wave::SquareWave;
+use crate::waveforms::SAMPLE_RATE;
 
 /// Handles key presses for musical notes, waveform toggling, and octave adjustments.
 ///
 /// # Parameters
 /// - `state`: Mutable reference to the synthesizer state which holds current octave, waveform, and pressed key.
 /// - `window`: Mutable reference to the window object used to detect key presses.
-/// - `sink`: Mutable reference to the audio sink where musical notes are played.
+/// - `engine`: Reference to the voice engine where musical notes are played.
 ///
 /// # Key Handling Logic
-/// - It iterates over predefined key mappings and triggers musical note generation when a corresponding key is pressed.
-/// - Toggles between SINE and SQUARE waveform when the 'F' key is pressed.
+/// - It iterates over predefined key mappings and starts a voice for every corresponding key pressed,
+///   allowing several notes to be started within the same frame.
+/// - Voices sustain for as long as their key is held and are stopped once the key is released.
+/// - Toggles the monophonic legato mode with 'F8', in which a key pressed while another is held slides the sounding
+///   voice to its note instead of starting a new one, and releasing it slides the voice back to the held note.
+/// - Cycles through the available waveforms when the 'S' key is pressed, and those of the second oscillator with 'D'.
+/// - Cycles the selected parameter of the second oscillator with 'O' and toggles its hard sync with 'H'.
+/// - Cycles how the second oscillator is combined with the first one with 'C'.
+/// - Cycles the selected parameter of the sub-oscillator and noise layer with 'B'.
+/// - Selects the FM operator to edit with 'J', cycles its selected parameter with 'K' and the FM algorithm with 'L'.
+/// - Selects the LFO to edit with 'Z', toggles between its rate and the tempo with 'I', cycles its shape with '8'
+///   and toggles its tempo sync with '0'.
+/// - Selects the route of the modulation matrix to edit with '1', and cycles its source with '9' and its destination with '4'.
+/// - Moves the mod wheel with 'PageUp'/'PageDown' and changes the velocity of new notes with '='/'-'.
+/// - Cycles the selected parameter of the unison stack with 'X'.
+/// - Cycles between the pan of new voices and the stereo width of the master bus with 'V'.
+/// - Selects the equalizer band to edit with 'Home', cycles its selected parameter with 'End' and toggles the equalizer with 'Insert'.
+/// - Selects the master effect to edit with 'F9', cycles its selected parameter with 'F10', bypasses it with 'F11',
+///   moves it later in the effects chain with 'F12' and toggles its mode with 'Tab'.
 /// - Increases the octave when 'F2' key is pressed and the current octave is below the upper bound.
 /// - Decreases the octave when 'F1' key is pressed and the current octave is above the lower bound.
-    pub fn handle_key_presses(state: &mut State, window: &mut Window, sink: &mut Sink) {
-        // Check for musical note key presses
-        for (key, note, _, _) in get_key_mappings() {
-            if window.is_key_pressed(key, KeyRepeat::No) {
-                handle_musical_note(state, sink, note);
-                state.pressed_key = Some((key, note));
-                return;
+/// - Cycles the selected envelope parameter when 'A' key is pressed and adjusts it with the 'Up'/'Down' keys.
+/// - Cycles the selected parameter of the filter envelope when 'F7' key is pressed.
+/// - Bends the pitch of all voices down or up for as long as the 'Left' or 'Right' key is held.
+pub fn handle_key_presses(state: &mut State, window: &mut Window, engine: &Engine) {
+    // Keys currently held down, used to catch releases which were not reported (e.g. when focus is lost)
+    let keys_down = window.get_keys();
+
+    // Check for musical note key presses and releases
+    for (key, note, _, _) in get_key_mappings() {
+        if window.is_key_pressed(key, KeyRepeat::No) && !state.is_key_held(key) {
+            let voice = match state.legato_voice() {
+                // Slide the sounding voice to the new note instead of starting another one,
+                // unless it has been stolen or has died out in the meantime
+                Some(voice) if engine.glide(voice, note.frequency(state.octave), state.glide_time()) => voice,
+                _ => handle_musical_note(state, engine, note)
+            };
+            state.hold_key(key, note, voice);
+        } else if window.is_key_released(key) || (state.is_key_held(key) && !keys_down.contains(&key)) {
+            if let Some(voice) = state.release_key(key) {
+                // A voice shared by several held keys slides back to the latest of them rather than being released
+                match state.held_note_of_voice(voice) {
+                    Some(held) => {
+                        engine.glide(voice, held.frequency(state.octave), state.glide_time());
+                    }
+                    None => engine.note_off(voice)
+                }
             }
         }
+    }
 
-    // Toggle the waveform between SINE and SQUARE when 'S' key is pressed
+    // Cycle through the waveforms when 'S' key is pressed
     if window.is_key_pressed(Key::S, KeyRepeat::No) {
-        state.toggle_waveform();
+        state.cycle_waveform();
+    }
+
+    // Cycle through the waveforms of the second oscillator when 'D' key is pressed
+    if window.is_key_pressed(Key::D, KeyRepeat::No) {
+        state.cycle_second_waveform();
+    }
+
+    // Select the next parameter of the second oscillator (mix, coarse detune, fine detune) when 'O' key is pressed
+    if window.is_key_pressed(Key::O, KeyRepeat::No) {
+        state.select_next_oscillator_parameter();
+    }
+
+    // Toggle hard sync of the second oscillator to the first one when 'H' key is pressed
+    if window.is_key_pressed(Key::H, KeyRepeat::No) {
+        state.toggle_oscillator_sync();
+    }
+
+    // Cycle the combiner of the oscillators between mix, ring modulation and amplitude modulation when 'C' key is pressed
+    if window.is_key_pressed(Key::C, KeyRepeat::No) {
+        state.cycle_oscillator_combiner();
+    }
+
+    // Select the next parameter of the sub-oscillator and noise layer (sub level, sub octave, noise level) when 'B' key is pressed
+    if window.is_key_pressed(Key::B, KeyRepeat::No) {
+        state.select_next_sub_noise_parameter();
+    }
+
+    // Select the next FM operator for editing when 'J' key is pressed
+    if window.is_key_pressed(Key::J, KeyRepeat::No) {
+        state.select_next_fm_operator();
+    }
+
+    // Select the next parameter of the selected FM operator (ratio, index, feedback) when 'K' key is pressed
+    if window.is_key_pressed(Key::K, KeyRepeat::No) {
+        state.select_next_fm_parameter();
+    }
+
+    // Cycle through the FM algorithms when 'L' key is pressed
+    if window.is_key_pressed(Key::L, KeyRepeat::No) {
+        state.cycle_fm_algorithm();
+    }
+
+    // Select the next LFO for editing when 'Z' key is pressed
+    if window.is_key_pressed(Key::Z, KeyRepeat::No) {
+        state.select_next_lfo();
+    }
+
+    // Toggle between the rate of the selected LFO and the tempo when 'I' key is pressed
+    if window.is_key_pressed(Key::I, KeyRepeat::No) {
+        state.select_next_lfo_parameter();
+    }
+
+    // Cycle the shape of the selected LFO when '8' key is pressed
+    if window.is_key_pressed(Key::Key8, KeyRepeat::No) {
+        state.cycle_lfo_shape();
+    }
+
+    // Toggle tempo sync of the selected LFO when '0' key is pressed
+    if window.is_key_pressed(Key::Key0, KeyRepeat::No) {
+        state.toggle_lfo_sync();
+    }
+
+    // Select the next route of the modulation matrix when '1' key is pressed
+    if window.is_key_pressed(Key::Key1, KeyRepeat::No) {
+        state.select_next_modulation_slot();
+    }
+
+    // Cycle the source of the selected route when '9' key is pressed
+    if window.is_key_pressed(Key::Key9, KeyRepeat::No) {
+        state.cycle_modulation_source();
+    }
+
+    // Cycle the destination of the selected route when '4' key is pressed
+    if window.is_key_pressed(Key::Key4, KeyRepeat::No) {
+        state.cycle_modulation_destination();
+    }
+
+    // Move the mod wheel up while 'PageUp' key is held and down while 'PageDown' key is held
+    if window.is_key_pressed(Key::PageUp, KeyRepeat::Yes) {
+        state.move_mod_wheel(1.0);
+    }
+    if window.is_key_pressed(Key::PageDown, KeyRepeat::Yes) {
+        state.move_mod_wheel(-1.0);
+    }
+
+    // Increase the velocity of new notes while '=' key is held and decrease it while '-' key is held
+    if window.is_key_pressed(Key::Equal, KeyRepeat::Yes) {
+        state.change_velocity(1.0);
+    }
+    if window.is_key_pressed(Key::Minus, KeyRepeat::Yes) {
+        state.change_velocity(-1.0);
+    }
+
+    // Select the next parameter of the unison stack (layers, detune, stereo spread) when 'X' key is pressed
+    if window.is_key_pressed(Key::X, KeyRepeat::No) {
+        state.select_next_unison_parameter();
+    }
+
+    // Select the next stereo parameter (pan, width) when 'V' key is pressed
+    if window.is_key_pressed(Key::V, KeyRepeat::No) {
+        state.select_next_stereo_parameter();
+    }
+
+    // Select the pulse width as the parameter adjusted by 'Up'/'Down' when 'P' key is pressed
+    if window.is_key_pressed(Key::P, KeyRepeat::No) {
+        state.select_pulse_width_parameter();
+    }
+
+    // Select the next wavetable when 'N' key is pressed
+    if window.is_key_pressed(Key::N, KeyRepeat::No) {
+        state.cycle_wavetable();
+    }
+
+    // Select the wavetable position as the parameter adjusted by 'Up'/'Down' when 'M' key is pressed
+    if window.is_key_pressed(Key::M, KeyRepeat::No) {
+        state.select_wavetable_position_parameter();
     }
 
     // Increase the octave when 'F2' key is pressed and the current octave is below the upper bound
@@ -57,50 +214,137 @@ use crate::waveforms::square_wave::SquareWave;
         state.toggle_lpf();
     }
 
-    // Increase the filter cutoff coefficient when 'F4' key is pressed
+    // Cycle the filter mode between low-pass, high-pass, band-pass and notch when 'G' key is pressed
+    if window.is_key_pressed(Key::G, KeyRepeat::No) {
+        state.cycle_filter_mode();
+    }
+
+    // Increase the filter cutoff frequency when 'F4' key is pressed
     if window.is_key_pressed(Key::F4, KeyRepeat::No) {
         state.increase_filter_cutoff();
     }
 
-    // Decrease the filter cutoff coefficient when 'F3' key is pressed
+    // Decrease the filter cutoff frequency when 'F3' key is pressed
     if window.is_key_pressed(Key::F3, KeyRepeat::No) {
         state.decrease_filter_cutoff();
     }
 
+    // Increase the filter resonance when 'F6' key is pressed
+    if window.is_key_pressed(Key::F6, KeyRepeat::No) {
+        state.increase_filter_resonance();
+    }
+
+    // Decrease the filter resonance when 'F5' key is pressed
+    if window.is_key_pressed(Key::F5, KeyRepeat::No) {
+        state.decrease_filter_resonance();
+    }
+
+    // Select the next filter envelope parameter (attack, decay, sustain, release, amount) when 'F7' key is pressed
+    if window.is_key_pressed(Key::F7, KeyRepeat::No) {
+        state.select_next_filter_envelope_parameter();
+    }
+
+    // Toggle the monophonic legato mode and select the glide time when 'F8' key is pressed
+    if window.is_key_pressed(Key::F8, KeyRepeat::No) {
+        state.toggle_legato();
+    }
+
+    // Select the next band of the equalizer (low shelf, two peaking bands, high shelf) when 'Home' key is pressed
+    if window.is_key_pressed(Key::Home, KeyRepeat::No) {
+        state.select_next_eq_band();
+    }
+
+    // Select the next parameter of the selected equalizer band (frequency, gain, Q) when 'End' key is pressed
+    if window.is_key_pressed(Key::End, KeyRepeat::No) {
+        state.select_next_eq_parameter();
+    }
+
+    // Turn the equalizer on the master output on/off when 'Insert' key is pressed
+    if window.is_key_pressed(Key::Insert, KeyRepeat::No) {
+        state.toggle_equalizer();
+    }
+
+    // Select the next effect of the master bus (delay, reverb, chorus, distortion, bitcrusher, compressor) when 'F9' key is pressed
+    if window.is_key_pressed(Key::F9, KeyRepeat::No) {
+        state.select_next_effect();
+    }
+
+    // Select the next parameter of the selected effect when 'F10' key is pressed
+    if window.is_key_pressed(Key::F10, KeyRepeat::No) {
+        state.select_next_effect_parameter();
+    }
+
+    // Bypass or enable the selected effect when 'F11' key is pressed
+    if window.is_key_pressed(Key::F11, KeyRepeat::No) {
+        state.toggle_effect();
+    }
+
+    // Move the selected effect one position later in the effects chain when 'F12' key is pressed
+    if window.is_key_pressed(Key::F12, KeyRepeat::No) {
+        state.move_effect_later();
+    }
+
+    // Toggle the tempo sync of the delay, switch the chorus between chorus and flanger, or cycle the shape of the distortion
+    // or the sidechain mode of the compressor when 'Tab' key is pressed
+    if window.is_key_pressed(Key::Tab, KeyRepeat::No) {
+        state.toggle_effect_mode();
+    }
+
+    // Select the next envelope parameter (attack, decay, sustain, release) when 'A' key is pressed
+    if window.is_key_pressed(Key::A, KeyRepeat::No) {
+        state.select_next_envelope_parameter();
+    }
+
+    // Increase the selected parameter while 'Up' key is held
+    if window.is_key_pressed(Key::Up, KeyRepeat::Yes) {
+        state.increase_selected_parameter();
+    }
+
+    // Decrease the selected parameter while 'Down' key is held
+    if window.is_key_pressed(Key::Down, KeyRepeat::Yes) {
+        state.decrease_selected_parameter();
+    }
+
+    // Bend the pitch down while 'Left' key is held and up while 'Right' key is held, returning to center on release
+    let bend_down = if window.is_key_down(Key::Left) { -1.0 } else { 0.0 };
+    let bend_up = if window.is_key_down(Key::Right) { 1.0 } else { 0.0 };
+    state.set_pitch_bend(bend_down + bend_up);
 }
 
 
-/// Handles playing a musical note with a specified octave, waveform, and duration.
+/// Starts a voice playing a musical note with the current octave and waveform.
 ///
 /// # Parameters
-/// - `octave`: A mutable reference to the current octave of the synthesizer.
-/// - `sink`: A mutable reference to the audio sink where the sound will be played.
-/// - `current_waveform`: The waveform enum representing the type of waveform to use for synthesizing the sound.
+/// - `state`: A mutable reference to the synthesizer state holding the current octave and waveform.
+/// - `engine`: A reference to the voice engine which allocates a voice for the sound.
 /// - `note`: The musical note (pitch) to be played.
-pub fn handle_musical_note(state: &mut State, sink: &mut Sink, note: Note) {
+///
+/// # Returns
+/// The identifier of the started voice, which sustains until it is stopped with [Engine::note_off].
+pub fn handle_musical_note(state: &mut State, engine: &Engine, note: Note) -> VoiceId {
 
     // Compute the base frequency association with the note and octave
     let base_frequency = note.frequency(state.octave);
 
-    // Initialize Synth implementation based on Waveform enum
-    let synth = match state.waveform {
-        Waveform::SQUARE => {
-            let filtered_frequency = state.apply_lpf(base_frequency);
-            let square_wave = SquareWave::new(filtered_frequency);
-            Box::new(square_wave) as Box<dyn Source<Item=f32> + 'static + Send>
-        }
-        _ => {
-            let filtered_frequency = state.apply_lpf(base_frequency);
-            let sine_wave = SineWave::new(filtered_frequency);
-            Box::new(sine_wave) as Box<dyn Source<Item=f32> + 'static + Send>
-        }
-    };
-
-    // Create Source from our Synth
-    let source = synth.take_duration(Duration::from_secs_f32(DURATION)).amplify(AMPLITUDE);
-
-    // Append the sound source to the audio sink for playback
-    let _result = sink.append(source);
+    // Seed noise generators differently for every note, so that stacked noise voices do not cancel out
+    let noise_seed = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().subsec_nanos();
+
+    // Create a pair of oscillators playing the selected waveforms for every layer of the unison stack,
+    // with the noise of every oscillator seeded differently
+    let layers = (0..state.unison_parameters().voices as u32).map(|layer| {
+        let seed = noise_seed.wrapping_add(layer.wrapping_mul(0x9E37_79B9));
+        (state.waveform().create(&state.oscillator_settings(base_frequency, seed)),
+         state.second_waveform().create(&state.oscillator_settings(base_frequency, seed.rotate_left(16))))
+    }).collect();
+
+    // Create a square wave sub-oscillator, which is tuned below the note by the voice engine, and a noise generator
+    // seeded apart from the noise of the oscillators
+    let sub_noise: SubNoiseSources = (Box::new(SquareWave::new(base_frequency * state.sub_noise_parameters().sub_frequency_ratio())),
+                                      Box::new(WhiteNoise::new(noise_seed.rotate_left(8))));
+
+    // Hand the oscillators to the voice engine, which shapes them with the envelope, places them at the selected pan
+    // and strikes them with the selected velocity alongside any other sounding notes
+    engine.note_on(layers, sub_noise, base_frequency, state.envelope, state.stereo_parameters().pan, state.velocity())
 }
 
 /// Draws the current state of the synthesizer on the window buffer.
@@ -111,7 +355,7 @@ pub fn handle_musical_note(state: &mut State, sink: &mut Sink, note: Note) {
 /// - `window_buffer`: Mutable reference to the window buffer where pixels are drawn.
 /// - `grid_width`: Width of the grid in tiles.
 /// - `grid_height`: Height of the grid in tiles.
-pub fn update_buffer_with_state(state: &State, sprites: &Sprites, window_buffer: &mut Vec<u32>, rack_index: usize, display_index: usize) {
+pub fn update_buffer_with_state(state: &State, sprites: &Sprites, window_buffer: &mut [u32], rack_index: usize, display_index: usize) {
 
     // Draw rack
     draw_rack_sprite(sprites, window_buffer, rack_index);
@@ -128,41 +372,66 @@ pub fn update_buffer_with_state(state: &State, sprites: &Sprites, window_buffer:
     // Draw the bulb
     draw_bulb_sprite(state, sprites, window_buffer);
 
+    // Draw the filter mode indicator next to the bulb
+    draw_filter_mode_sprite(state, sprites, window_buffer);
+
     // Draw the cutoff knob for LPF
     draw_filter_cutoff_knob_sprite(state, sprites, window_buffer);
 
-    // Draw the idle knob to the left of the cutoff knob for LPF
-    draw_idle_knob_sprite(sprites, window_buffer);
+    // Draw the resonance knob to the right of the cutoff knob for LPF
+    draw_resonance_knob_sprite(state, sprites, window_buffer);
+
+    // Draw the level knobs of the sub-oscillator and noise layer above the cutoff and resonance knobs
+    draw_sub_noise_knob_sprites(state, sprites, window_buffer);
 
     // Draw octave fader, which display the current octave controlled by keys F1/F2
     draw_octave_fader_sprite(state.octave, sprites, window_buffer);
 
-    let sprite = match state.waveform {
-        Waveform::SINE => &sprites.display_sine,
-        Waveform::SQUARE => &sprites.display_square
-    };
+    // Draw the waveform display of the first oscillator, and that of the second oscillator above it
+    let display_height = sprites.displays[WAVEFORM_SINE][0].height as usize;
+    draw_waveform_display(state, state.waveform(), sprites, window_buffer, display_index, 4 * display_height + 17);
+    draw_waveform_display(state, state.second_waveform(), sprites, window_buffer, display_index, 3 * display_height + 12);
+
+    // Draw the mix, detune and sync of the second oscillator on top of its waveform display
+    draw_second_oscillator_readout(state, sprites, window_buffer);
+
+    // Draw the envelope stage of the latest voice and the selected parameter on top of the waveform display
+    draw_envelope_readout(state, sprites, window_buffer);
 
-    draw_display_sprite(sprite, window_buffer, display_index);
+    // Draw the mod wheel, velocity and active routes of the modulation matrix above the rack
+    draw_modulation_overlay(state, sprites, window_buffer);
 
-    // Check if a key is pressed
+    // Draw the order of the master effects chain and the gain reduction of the limiter across from the modulation overlay
+    draw_effects_chain(state, sprites, window_buffer);
+
+    // Draw the most recently pressed note on the note display, followed by the octave it sounds in
     if let Some((_, note)) = &state.pressed_key {
+        let note_sprite_index = get_note_sprite_index(note).unwrap_or_default();
+        draw_note_sprite(sprites, window_buffer, note_sprite_index);
+        draw_number_sprite(sprites, window_buffer, state.octave as usize);
+    }
+
+    // Collect the sprite indices of every note currently held down
+    let mut pressed_note_sprite_indices = Vec::new();
 
+    for (_, note, _) in &state.held_keys {
         // Get sprite index associated with the note to be drawn (A, C# etc.)
         let note_sprite_index = get_note_sprite_index(note).unwrap_or_default();
 
         // Get key position on the keyboard (0 would be the first key, 7 the last etc.)
         let key_position = get_key_position(note).unwrap_or(0);
 
-        // Draw sprites note, knobs and the waveform display
-        draw_note_sprite(sprites, window_buffer, note_sprite_index);
-
         // Draw pressed key sprite if the note is not a sharp
         if matches!(note, Note::A | Note::B | Note::C | Note::D | Note::E | Note::F | Note::G) {
             draw_pressed_key_sprite(sprites, window_buffer, key_position);
         }
 
-        // Draw idle and pressed tangents as overlay on key sprites
-        draw_tangent_sprites(note_sprite_index, &tangent_map, sprites, window_buffer);
+        pressed_note_sprite_indices.push(note_sprite_index);
+    }
+
+    // Draw idle and pressed tangents as overlay on key sprites
+    if !pressed_note_sprite_indices.is_empty() {
+        draw_tangent_sprites(&pressed_note_sprite_indices, &tangent_map, sprites, window_buffer);
     }
 }
 
@@ -250,20 +519,215 @@ pub fn create_tangent_map() -> HashMap<i32, usize> {
 /// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
 /// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
 pub fn draw_rack_sprite(sprites: &Sprites, buffer: &mut [u32], rack_index: usize) {
-    draw_sprite(0 * sprites.rack[0].width as usize,
-                0 * sprites.rack[0].height as usize,
+    draw_sprite(0,
+                0,
                 &sprites.rack[rack_index], buffer, WINDOW_WIDTH);
 }
 
-/// Draws the sine wave sprite.
+/// Draws a waveform display showing the given waveform, with the name of the waveform in the upper right corner.
 ///
 /// # Parameters
+/// - `state`: Reference to the current `State` containing the state of the synthesizer.
+/// - `waveform`: The waveform to be shown on the display.
+/// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
+/// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
+/// - `display_index`: The animation frame of the display.
+/// - `y`: The y-coordinate of the top of the display.
+pub fn draw_waveform_display(state: &State, waveform: &'static WaveformDefinition, sprites: &Sprites, window_buffer: &mut [u32], display_index: usize, y: usize) {
+    // Oscillators drawing their own trace are rendered at exactly TRACE_RESOLUTION samples per period
+    let settings = state.oscillator_settings(SAMPLE_RATE / TRACE_RESOLUTION as f32, 0);
+
+    let sprite = match waveform.display {
+        WaveformDisplay::Sprite(sprite) => {
+            draw_display_sprite(&sprites.displays[sprite], window_buffer, display_index, y);
+            sprite
+        }
+        WaveformDisplay::Traced(sprite) => {
+            // The sprite map is a single background frame, as the trace depends on the settings of the oscillator
+            draw_display_sprite(&sprites.displays[sprite], window_buffer, 0, y);
+            draw_oscillator_trace(waveform, &settings, sprites, window_buffer, display_index, y);
+            sprite
+        }
+    };
+
+    // Right-align the name of the waveform in the upper right corner
+    let display_width = sprites.displays[sprite][0].width as usize;
+    let name = waveform.label(&settings);
+    let name_width = name.len() * sprites.font[0].width as usize;
+    draw_text((2 * display_width - 4).saturating_sub(name_width), y + 3, &name, sprites, window_buffer);
+}
+
+/// Draws a frame of a waveform display sprite.
+///
+/// # Parameters
+/// - `sprite`: The animation frames of the waveform display.
+/// - `buffer`: A mutable reference to the buffer representing the window's pixels.
+/// - `display_index`: The animation frame to be drawn.
+/// - `y`: The y-coordinate of the top of the display.
+pub fn draw_display_sprite(sprite: &[Sprite], buffer: &mut [u32], display_index: usize, y: usize) {
+    draw_sprite(sprite[0].width as usize, y, &sprite[display_index], buffer, WINDOW_WIDTH);
+}
+
+/// Draws a period rendered by an oscillator playing the given waveform onto the waveform display.
+///
+/// # Parameters
+/// - `waveform`: The waveform whose oscillator renders the trace.
+/// - `settings`: The settings the oscillator is created from, at a frequency of [TRACE_RESOLUTION] samples per period.
+/// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
+/// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
+/// - `display_index`: The animation frame, which shifts the phase of the trace.
+/// - `y`: The y-coordinate of the top of the display.
+pub fn draw_oscillator_trace(waveform: &WaveformDefinition, settings: &OscillatorSettings, sprites: &Sprites, window_buffer: &mut [u32], display_index: usize, y: usize) {
+    // Discard the first period, so that feedback and smoothing inside the oscillator have settled
+    let period: Vec<f32> = waveform.create(settings).skip(TRACE_RESOLUTION).take(TRACE_RESOLUTION).collect();
+
+    draw_display_trace(sprites, window_buffer, display_index, y, |phase| period[(phase * TRACE_RESOLUTION as f32) as usize % TRACE_RESOLUTION]);
+}
+
+/// Draws a periodic trace onto the waveform display, in the same color and scale as the waveform sprites.
+///
+/// # Parameters
+/// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
+/// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
+/// - `display_index`: The animation frame, which shifts the phase of the trace.
+/// - `y`: The y-coordinate of the top of the display.
+/// - `sample`: Returns the value in the range [-1.0, 1.0] at a phase in the range [0.0, 1.0).
+pub fn draw_display_trace(sprites: &Sprites, window_buffer: &mut [u32], display_index: usize, y: usize, sample: impl Fn(f32) -> f32) {
+    let display_width = sprites.displays[WAVEFORM_WAVETABLE][0].width as usize;
+    let x = display_width;
+
+    // Draw two periods across the display, spanning the same height as the other waveform sprites
+    let period = 32.0;
+    let center = y as f32 + 28.5;
+    let amplitude = 10.5;
+
+    let mut previous_row: Option<usize> = None;
+    for column in 1..display_width - 1 {
+        let phase = ((column as f32 + display_index as f32 * period / 6.0) / period).fract();
+        let value = sample(phase).clamp(-1.0, 1.0);
+        let row = (center - value * amplitude).round() as usize;
+
+        // Connect consecutive samples with a vertical line so steep edges remain visible
+        let (top, bottom) = match previous_row {
+            Some(previous) => (previous.min(row), previous.max(row)),
+            None => (row, row)
+        };
+        for trace_row in top..=bottom {
+            window_buffer[trace_row * WINDOW_WIDTH + x + column] = DISPLAY_TRACE_COLOR;
+        }
+
+        previous_row = Some(row);
+    }
+}
+
+/// Draws the combiner and mix of the second oscillator in the upper left corner of its waveform display,
+/// its detune in the lower left corner and whether it is hard synced in the lower right corner.
+///
+/// # Parameters
+/// - `state`: Reference to the current `State` containing the state of the synthesizer.
 /// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
 /// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
-pub fn draw_display_sprite(sprite: &Vec<Sprite>, buffer: &mut [u32], display_index: usize) {
-    draw_sprite(1 * sprite[0].width as usize,
-                4 * sprite[0].height as usize + 17,
-                &sprite[display_index], buffer, WINDOW_WIDTH);
+pub fn draw_second_oscillator_readout(state: &State, sprites: &Sprites, window_buffer: &mut [u32]) {
+    let display_width = sprites.displays[WAVEFORM_SINE][0].width as usize;
+    let display_height = sprites.displays[WAVEFORM_SINE][0].height as usize;
+    let x = display_width + 4;
+    let y = 3 * display_height + 12;
+    let bottom = y + display_height - sprites.font[0].height as usize - 3;
+
+    let parameters = state.second_oscillator_parameters();
+    draw_text(x, y + 3, &format!("{} {:.0}%", parameters.combiner, parameters.mix * 100.0), sprites, window_buffer);
+    draw_text(x, bottom, &format!("{:+}st {:+}ct", parameters.coarse, parameters.fine), sprites, window_buffer);
+
+    if parameters.sync {
+        let label = "SYNC";
+        draw_text(2 * display_width - 4 - label.len() * sprites.font[0].width as usize, bottom, label, sprites, window_buffer);
+    }
+}
+
+/// Draws the envelope stage of the most recently started voice in the upper left corner of the waveform display
+/// and the selected parameter along with its value in the lower left corner.
+///
+/// # Parameters
+/// - `state`: Reference to the current `State` containing the state of the synthesizer.
+/// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
+/// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
+pub fn draw_envelope_readout(state: &State, sprites: &Sprites, window_buffer: &mut [u32]) {
+    let x = sprites.displays[WAVEFORM_SINE][0].width as usize + 4;
+    let y = 4 * sprites.displays[WAVEFORM_SINE][0].height as usize + 17;
+
+    draw_text(x, y + 3, &state.envelope_stage.to_string(), sprites, window_buffer);
+    draw_text(x, y + sprites.displays[WAVEFORM_SINE][0].height as usize - sprites.font[0].height as usize - 3,
+              &state.describe_selected_parameter(), sprites, window_buffer);
+}
+
+/// Draws the position of the mod wheel, the velocity of new notes and the active routes of the modulation matrix
+/// as text in the upper left corner of the window, above the rack. The route selected for editing is marked with '>'
+/// and always listed, even while it is inactive.
+///
+/// # Parameters
+/// - `state`: Reference to the current `State` containing the state of the synthesizer.
+/// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
+/// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
+pub fn draw_modulation_overlay(state: &State, sprites: &Sprites, window_buffer: &mut [u32]) {
+    let line_height = sprites.font[0].height as usize;
+    let (x, mut y) = (8, 8);
+
+    let header = format!("MOD WHL {:.0}% VEL {:.0}%", state.modulation_parameters().mod_wheel * 100.0, state.velocity() * 100.0);
+    draw_text(x, y, &header, sprites, window_buffer);
+
+    let (slots, selected) = state.modulation_slots();
+    for (index, slot) in slots.iter().enumerate() {
+        if !slot.is_active() && index != selected {
+            continue;
+        }
+
+        y += line_height;
+        let marker = if index == selected { '>' } else { ' ' };
+        let route = format!("{}{} {}>{} {:+.0}%", marker, index + 1, slot.source, slot.destination, slot.amount * 100.0);
+        draw_text(x, y, &route, sprites, window_buffer);
+    }
+}
+
+/// Draws the effects of the master effects chain in the order they process the signal, followed by the compressor
+/// and the equalizer, as text in the upper right corner of the window, above the rack, and the gain reduction of the master limiter
+/// on the line below.
+/// Bypassed effects are drawn in lower case.
+///
+/// # Parameters
+/// - `state`: Reference to the current `State` containing the state of the synthesizer.
+/// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
+/// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
+pub fn draw_effects_chain(state: &State, sprites: &Sprites, window_buffer: &mut [u32]) {
+    let labels: Vec<String> = state.effects_parameters().order.iter().map(|effect| state.effect_label((*effect).into())).collect();
+    let equalizer = if state.equalizer_parameters().enabled { "EQ" } else { "eq" };
+    let chain = format!("FX {}>{}>{}", labels.join(">"), state.effect_label(EffectKind::Compressor), equalizer);
+
+    let limiter = format!("LIM -{:.1}dB", state.gain_reduction());
+
+    let font_width = sprites.font[0].width as usize;
+    draw_text(WINDOW_WIDTH.saturating_sub(8 + chain.len() * font_width), 8, &chain, sprites, window_buffer);
+    draw_text(WINDOW_WIDTH.saturating_sub(8 + limiter.len() * font_width), 8 + sprites.font[0].height as usize,
+              &limiter, sprites, window_buffer);
+}
+
+/// Draws a line of text with the font sprites, starting at the given coordinates.
+/// Characters outside of the printable ASCII range are drawn as '?'.
+///
+/// # Parameters
+/// - `x`: The x-coordinate of the first character.
+/// - `y`: The y-coordinate of the top of the text.
+/// - `text`: The text to be drawn.
+/// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
+/// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
+pub fn draw_text(x: usize, y: usize, text: &str, sprites: &Sprites, window_buffer: &mut [u32]) {
+    for (i, character) in text.chars().enumerate() {
+        let glyph = if (' '..='~').contains(&character) { character } else { '?' };
+        let glyph_index = glyph as usize - ' ' as usize;
+
+        draw_sprite(x + i * sprites.font[glyph_index].width as usize,
+                    y,
+                    &sprites.font[glyph_index], window_buffer, WINDOW_WIDTH);
+    }
 }
 
 /// Draws the pressed key sprite.
@@ -271,7 +735,7 @@ pub fn draw_display_sprite(sprite: &Vec<Sprite>, buffer: &mut [u32], display_ind
 /// # Parameters
 /// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
 /// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
-pub fn draw_pressed_key_sprite(sprites: &Sprites, window_buffer: &mut Vec<u32>, key_position: usize) {
+pub fn draw_pressed_key_sprite(sprites: &Sprites, window_buffer: &mut [u32], key_position: usize) {
     draw_sprite(key_position * sprites.keys[KEY_PRESSED].width as usize,
                 2 * sprites.keys[KEY_PRESSED].height as usize,
                 &sprites.keys[KEY_PRESSED], window_buffer, WINDOW_WIDTH);
@@ -284,7 +748,7 @@ pub fn draw_pressed_key_sprite(sprites: &Sprites, window_buffer: &mut Vec<u32>,
 /// - `octave`: The current octave.
 /// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
 /// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
-pub fn draw_octave_fader_sprite(octave: i32, sprites: &Sprites, window_buffer: &mut Vec<u32>) {
+pub fn draw_octave_fader_sprite(octave: i32, sprites: &Sprites, window_buffer: &mut [u32]) {
     draw_sprite(8 * sprites.keys[0].width as usize + 5,
                 2 * sprites.keys[0].height as usize,
                 &sprites.octave_fader[octave as usize], window_buffer, WINDOW_WIDTH);
@@ -296,57 +760,91 @@ pub fn draw_octave_fader_sprite(octave: i32, sprites: &Sprites, window_buffer: &
 /// # Parameters
 /// - `window`: Mutable reference to the `Window` object where the visuals are displayed.
 /// - `window_buffer`: Mutable reference to a vector of `u32` representing the pixel data to be displayed.
-pub fn draw_buffer(window: &mut Window, window_buffer: &mut Vec<u32>) {
-    window.update_with_buffer(&window_buffer, WINDOW_WIDTH, WINDOW_HEIGHT).unwrap();
+pub fn draw_buffer(window: &mut Window, window_buffer: &mut [u32]) {
+    window.update_with_buffer(window_buffer, WINDOW_WIDTH, WINDOW_HEIGHT).unwrap();
 }
 
-/// Draws idle knobs.
+/// Draws the bulb, which is lit while the filter is active.
 ///
 /// # Parameters
 /// - `state`: Reference to the current `State` containing the state of the synthesizer.
 /// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
 /// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
-pub fn draw_bulb_sprite(state: &State, sprites: &Sprites, window_buffer: &mut Vec<u32>) {
+pub fn draw_bulb_sprite(state: &State, sprites: &Sprites, window_buffer: &mut [u32]) {
+    let bulb_sprite_index = usize::from(state.filter_mode != FilterMode::Off);
+
     draw_sprite(6 * sprites.knob[0].width as usize,
                 5 * sprites.knob[0].height as usize + 10,
-                &sprites.bulb[state.lpf_active], window_buffer, WINDOW_WIDTH);
+                &sprites.bulb[bulb_sprite_index], window_buffer, WINDOW_WIDTH);
 }
 
-/// Draws idle knobs.
+/// Draws the filter mode label (LP, HP, BP or NO) over the label printed to the left of the bulb.
+/// The printed LP label is left as is while the filter is off.
 ///
 /// # Parameters
 /// - `state`: Reference to the current `State` containing the state of the synthesizer.
 /// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
 /// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
-pub fn draw_filter_cutoff_knob_sprite(state: &State, sprites: &Sprites, window_buffer: &mut Vec<u32>) {
-    let filter_cutoff = state.filter_factor;
-
-    // Assigns the appropriate sprite index based on cutoff float value threshold
-    let knob_sprite_index = match filter_cutoff {
-        v if (0.0..=0.14).contains(&v) => 0,
-        v if (0.14..=0.28).contains(&v) => 1,
-        v if (0.28..=0.42).contains(&v) => 2,
-        v if (0.42..=0.57).contains(&v) => 3,
-        v if (0.57..=0.71).contains(&v) => 4,
-        v if (0.71..=0.85).contains(&v) => 5,
-        v if (0.85..=0.99).contains(&v) => 6,
-        _ => 7 // Last knob for ~0.99
+pub fn draw_filter_mode_sprite(state: &State, sprites: &Sprites, window_buffer: &mut [u32]) {
+    let filter_mode_sprite_index = match state.filter_mode {
+        FilterMode::Off => return,
+        FilterMode::LowPass => FILTER_MODE_LOW_PASS,
+        FilterMode::HighPass => FILTER_MODE_HIGH_PASS,
+        FilterMode::BandPass => FILTER_MODE_BAND_PASS,
+        FilterMode::Notch => FILTER_MODE_NOTCH
     };
 
-    draw_sprite(6 * sprites.knob[0].width as usize,
-                5 * sprites.knob[0].height as usize - 10,
-                &sprites.knob[knob_sprite_index], window_buffer, WINDOW_WIDTH);
+    draw_sprite(6 * sprites.knob[0].width as usize - 20,
+                5 * sprites.knob[0].height as usize + 9,
+                &sprites.filter_modes[filter_mode_sprite_index], window_buffer, WINDOW_WIDTH);
 }
 
-/// Draws idle knob.
+/// Draws the filter cutoff knob, following the cutoff as modulated by the LFOs.
 ///
 /// # Parameters
+/// - `state`: Reference to the current `State` containing the state of the synthesizer.
 /// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
 /// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
-pub fn draw_idle_knob_sprite(sprites: &Sprites, window_buffer: &mut Vec<u32>) {
-    draw_sprite(7 * sprites.knob[0].width as usize,
-                5 * sprites.knob[0].height as usize - 10,
-                &sprites.knob[0], window_buffer, WINDOW_WIDTH);
+pub fn draw_filter_cutoff_knob_sprite(state: &State, sprites: &Sprites, window_buffer: &mut [u32]) {
+    // Each knob sprite represents one octave above the minimum cutoff frequency
+    let octaves_above_minimum = (state.modulated_cutoff / MIN_CUTOFF).log2().round() as usize;
+    let knob_sprite_index = octaves_above_minimum.min(sprites.knob.len() - 1);
+
+    draw_sprite(FILTER_KNOB_COLUMN * KNOB_WIDTH, FILTER_KNOB_Y, &sprites.knob[knob_sprite_index], window_buffer, WINDOW_WIDTH);
+}
+
+/// Draws the filter resonance knob.
+///
+/// # Parameters
+/// - `state`: Reference to the current `State` containing the state of the synthesizer.
+/// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
+/// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
+pub fn draw_resonance_knob_sprite(state: &State, sprites: &Sprites, window_buffer: &mut [u32]) {
+    // Spread the resonance range evenly across the knob sprites
+    let knob_sprite_index = ((state.filter_resonance / MAX_RESONANCE) * (sprites.knob.len() - 1) as f32).round() as usize;
+
+    draw_sprite((FILTER_KNOB_COLUMN + 1) * KNOB_WIDTH, FILTER_KNOB_Y, &sprites.knob[knob_sprite_index], window_buffer, WINDOW_WIDTH);
+}
+
+/// Draws the level knobs of the sub-oscillator and noise layer, each labelled with the font sprites,
+/// in the upper right corner of the rack above the cutoff and resonance knobs.
+///
+/// # Parameters
+/// - `state`: Reference to the current `State` containing the state of the synthesizer.
+/// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
+/// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
+pub fn draw_sub_noise_knob_sprites(state: &State, sprites: &Sprites, window_buffer: &mut [u32]) {
+    let font_width = sprites.font[0].width as usize;
+    let parameters = state.sub_noise_parameters();
+
+    for (column, label, level) in [(FILTER_KNOB_COLUMN, "Sub", parameters.sub_level), (FILTER_KNOB_COLUMN + 1, "Noise", parameters.noise_level)] {
+        // Spread the level range evenly across the knob sprites
+        let knob_sprite_index = (level * (sprites.knob.len() - 1) as f32).round() as usize;
+        let x = column * KNOB_WIDTH;
+
+        draw_text(x + (KNOB_WIDTH - label.len() * font_width) / 2, SUB_NOISE_KNOB_Y - KNOB_LABEL_OFFSET, label, sprites, window_buffer);
+        draw_sprite(x, SUB_NOISE_KNOB_Y, &sprites.knob[knob_sprite_index], window_buffer, WINDOW_WIDTH);
+    }
 }
 
 /// Draws the note sprite for the given note sprite index.
@@ -355,19 +853,31 @@ pub fn draw_idle_knob_sprite(sprites: &Sprites, window_buffer: &mut Vec<u32>) {
 /// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
 /// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
 /// - `note_sprite_index`: The index of the note sprite to be drawn.
-pub fn draw_note_sprite(sprites: &Sprites, window_buffer: &mut Vec<u32>, note_sprite_index: usize) {
-    draw_sprite(1 * sprites.notes[0].width as usize,
+pub fn draw_note_sprite(sprites: &Sprites, window_buffer: &mut [u32], note_sprite_index: usize) {
+    draw_sprite(sprites.notes[0].width as usize,
                 5 * sprites.notes[0].height as usize - 15,
                 &sprites.notes[note_sprite_index], window_buffer, WINDOW_WIDTH);
 }
 
+/// Draws the number sprite for the given digit right after the note sprite.
+///
+/// # Parameters
+/// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
+/// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
+/// - `digit`: The digit to be drawn, from 0 to 9.
+pub fn draw_number_sprite(sprites: &Sprites, window_buffer: &mut [u32], digit: usize) {
+    draw_sprite(sprites.notes[0].width as usize + 24,
+                5 * sprites.notes[0].height as usize - 15,
+                &sprites.numbers[digit], window_buffer, WINDOW_WIDTH);
+}
+
 /// Draws all idle tangents (sharp keys).
 ///
 /// # Parameters
 /// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
 /// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
 /// - `tangent_map`: A hashmap mapping positions to the corresponding tangent note sprite indices.
-pub fn draw_idle_tangent_sprites(sprites: &Sprites, window_buffer: &mut Vec<u32>, tangent_map: &HashMap<i32, usize>) {
+pub fn draw_idle_tangent_sprites(sprites: &Sprites, window_buffer: &mut [u32], tangent_map: &HashMap<i32, usize>) {
     let key_width = sprites.keys[KEY_IDLE].width as i32;
     let key_height = sprites.keys[KEY_IDLE].height as usize;
     let tangent_width = sprites.tangents[TANGENT_IDLE].width as i32;
@@ -394,7 +904,7 @@ pub fn draw_idle_tangent_sprites(sprites: &Sprites, window_buffer: &mut Vec<u32>
 /// # Parameters
 /// - `sprites`: A reference to the `Sprites` struct containing all the sprite images.
 /// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
-pub fn draw_idle_key_sprites(sprites: &Sprites, window_buffer: &mut Vec<u32>) {
+pub fn draw_idle_key_sprites(sprites: &Sprites, window_buffer: &mut [u32]) {
     for i in 1..8 {
         draw_sprite(
             i * sprites.keys[KEY_IDLE].width as usize,
@@ -409,16 +919,16 @@ pub fn draw_idle_key_sprites(sprites: &Sprites, window_buffer: &mut Vec<u32>) {
 /// Draws the tangents (sharp keys).
 ///
 /// # Parameters
-/// - `note_sprite_index`: The index of the sprite representing the current note being pressed.
+/// - `pressed_note_sprite_indices`: The indices of the sprites representing the notes currently held down.
 /// - `tangent_map`: A hashmap mapping positions to the corresponding tangent note sprite indices.
 /// - `sprites`: The `Sprites` struct containing all the sprite images.
 /// - `window_buffer`: A mutable reference to the buffer representing the window's pixels.
-pub fn draw_tangent_sprites(note_sprite_index: usize, tangent_map: &HashMap<i32, usize>, sprites: &Sprites, window_buffer: &mut Vec<u32>) {
+pub fn draw_tangent_sprites(pressed_note_sprite_indices: &[usize], tangent_map: &HashMap<i32, usize>, sprites: &Sprites, window_buffer: &mut [u32]) {
     let key_width = sprites.keys[KEY_IDLE].width as i32;
     let key_height = sprites.keys[KEY_IDLE].height as usize;
 
     for (&pos, &tangent) in tangent_map {
-        let tangent_sprite_index = if note_sprite_index == tangent {
+        let tangent_sprite_index = if pressed_note_sprite_indices.contains(&tangent) {
             TANGENT_PRESSED
         } else {
             TANGENT_IDLE
@@ -0,0 +1,53 @@
+use std::fmt;
+
+/// Enumerates the ways the first oscillator of a voice (the carrier) is combined with the second one (the modulator)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OscillatorCombiner {
+    /// Crossfades between the oscillators.
+    Mix,
+    /// Multiplies the oscillators, which yields their sum and difference frequencies but neither of the originals.
+    /// Detuned to inharmonic ratios this produces bell-like and metallic tones.
+    RingModulation,
+    /// Varies the level of the carrier with the modulator, which keeps the carrier and adds the sum and difference
+    /// frequencies as sidebands.
+    AmplitudeModulation
+}
+
+impl OscillatorCombiner {
+    /// Returns the next combiner in the order mix, ring modulation and amplitude modulation.
+    pub fn next(&self) -> OscillatorCombiner {
+        match self {
+            OscillatorCombiner::Mix => OscillatorCombiner::RingModulation,
+            OscillatorCombiner::RingModulation => OscillatorCombiner::AmplitudeModulation,
+            OscillatorCombiner::AmplitudeModulation => OscillatorCombiner::Mix
+        }
+    }
+
+    /// Combines a sample of the carrier with a sample of the modulator.
+    ///
+    /// # Parameters
+    /// - `carrier`: The sample of the first oscillator.
+    /// - `modulator`: The sample of the second oscillator.
+    /// - `amount`: The balance between the carrier alone at 0.0 and the fully combined signal at 1.0.
+    pub fn combine(&self, carrier: f32, modulator: f32, amount: f32) -> f32 {
+        let combined = match self {
+            OscillatorCombiner::Mix => modulator,
+            OscillatorCombiner::RingModulation => carrier * modulator,
+            // Offset the modulator into the range [0.0, 1.0] so that it only ever scales the carrier down
+            OscillatorCombiner::AmplitudeModulation => carrier * 0.5 * (1.0 + modulator)
+        };
+
+        carrier * (1.0 - amount) + combined * amount
+    }
+}
+
+/// Implements the [Display] trait for [OscillatorCombiner]
+impl fmt::Display for OscillatorCombiner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OscillatorCombiner::Mix => write!(f, "OSC2"),
+            OscillatorCombiner::RingModulation => write!(f, "RING"),
+            OscillatorCombiner::AmplitudeModulation => write!(f, "AM")
+        }
+    }
+}
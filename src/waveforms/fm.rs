@@ -0,0 +1,230 @@
+use std::f32::consts::PI;
+use std::fmt;
+use std::time::Duration;
+
+use rodio::Source;
+
+use crate::waveforms::{
+    phase_accumulator::PhaseAccumulator,
+    Oscillator, MONO, SAMPLE_RATE
+};
+
+/// Number of operators of the FM oscillator, of which the two-operator algorithm only uses the first two
+pub const FM_OPERATORS: usize = 4;
+
+pub const MIN_FM_RATIO: f32 = 0.5;
+pub const MAX_FM_RATIO: f32 = 16.0;
+pub const MAX_FM_INDEX: f32 = 10.0;
+pub const MAX_FM_FEEDBACK: f32 = 1.0;
+
+/// Enumerates the ways in which the operators modulate each other, written from modulator to carrier.
+/// Operator 1 is always a carrier, and operators only ever modulate operators with a lower number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FmAlgorithm {
+    /// Operator 2 modulates operator 1, operators 3 and 4 are silent.
+    TwoOperator,
+    /// Operator 4 modulates 3, which modulates 2, which modulates 1.
+    Stack,
+    /// Operator 2 modulates 1 and operator 4 modulates 3, with both 1 and 3 heard.
+    TwoStacks,
+    /// Operators 2, 3 and 4 each modulate operator 1.
+    ThreeToOne,
+    /// All four operators are heard without modulating each other.
+    Additive
+}
+
+impl FmAlgorithm {
+    /// Returns the next algorithm, wrapping around after the last one.
+    pub fn next(&self) -> FmAlgorithm {
+        match self {
+            FmAlgorithm::TwoOperator => FmAlgorithm::Stack,
+            FmAlgorithm::Stack => FmAlgorithm::TwoStacks,
+            FmAlgorithm::TwoStacks => FmAlgorithm::ThreeToOne,
+            FmAlgorithm::ThreeToOne => FmAlgorithm::Additive,
+            FmAlgorithm::Additive => FmAlgorithm::TwoOperator
+        }
+    }
+
+    /// Returns the zero-based indices of the operators modulating the given operator.
+    fn modulators(&self, operator: usize) -> &'static [usize] {
+        match (self, operator) {
+            (FmAlgorithm::TwoOperator, 0) => &[1],
+            (FmAlgorithm::Stack, 0) => &[1],
+            (FmAlgorithm::Stack, 1) => &[2],
+            (FmAlgorithm::Stack, 2) => &[3],
+            (FmAlgorithm::TwoStacks, 0) => &[1],
+            (FmAlgorithm::TwoStacks, 2) => &[3],
+            (FmAlgorithm::ThreeToOne, 0) => &[1, 2, 3],
+            _ => &[]
+        }
+    }
+
+    /// Returns the zero-based indices of the operators which are heard.
+    fn carriers(&self) -> &'static [usize] {
+        match self {
+            FmAlgorithm::TwoOperator | FmAlgorithm::Stack | FmAlgorithm::ThreeToOne => &[0],
+            FmAlgorithm::TwoStacks => &[0, 2],
+            FmAlgorithm::Additive => &[0, 1, 2, 3]
+        }
+    }
+
+    /// Returns the number of operators taking part in the algorithm.
+    fn operator_count(&self) -> usize {
+        match self {
+            FmAlgorithm::TwoOperator => 2,
+            _ => FM_OPERATORS
+        }
+    }
+}
+
+/// Implements the [Display] trait for [FmAlgorithm]
+impl fmt::Display for FmAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FmAlgorithm::TwoOperator => write!(f, "FM 2>1"),
+            FmAlgorithm::Stack => write!(f, "FM 4>3>2>1"),
+            FmAlgorithm::TwoStacks => write!(f, "FM 2>1 4>3"),
+            FmAlgorithm::ThreeToOne => write!(f, "FM 234>1"),
+            FmAlgorithm::Additive => write!(f, "FM 1+2+3+4")
+        }
+    }
+}
+
+/// Settings of a single operator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FmOperatorParameters {
+    /// Frequency of the operator relative to the played note.
+    pub ratio: f32,
+    /// Modulation index, i.e. the peak phase deviation in radians the operator causes in the operators it modulates.
+    /// The index of a carrier is unused, as carriers are heard at full level.
+    pub index: f32,
+    /// Amount of the operator's own output fed back into its phase, from 0.0 (none) to 1.0 (a deviation of π radians).
+    pub feedback: f32
+}
+
+/// Settings of the FM oscillator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FmParameters {
+    pub algorithm: FmAlgorithm,
+    pub operators: [FmOperatorParameters; FM_OPERATORS]
+}
+
+impl Default for FmParameters {
+    fn default() -> Self {
+        FmParameters {
+            algorithm: FmAlgorithm::TwoOperator,
+            operators: [
+                FmOperatorParameters { ratio: 1.0, index: 1.0, feedback: 0.0 },
+                FmOperatorParameters { ratio: 1.0, index: 2.0, feedback: 0.0 },
+                FmOperatorParameters { ratio: 3.0, index: 1.0, feedback: 0.0 },
+                FmOperatorParameters { ratio: 1.0, index: 1.0, feedback: 0.0 }
+            ]
+        }
+    }
+}
+
+/// A sine operator of the [FmOscillator], remembering its last two outputs for feedback
+#[derive(Debug)]
+struct Operator {
+    phase: PhaseAccumulator,
+    parameters: FmOperatorParameters,
+    output: [f32; 2]
+}
+
+/// Frequency modulation oscillator, in which up to four sine operators modulate each other's phase according to an algorithm.
+///
+/// Unlike the subtractive waveforms, the harmonics are created by the modulation itself, so bright and bell-like
+/// spectra are reached without filtering. Strictly speaking this is phase modulation, as done by most FM synthesizers.
+#[derive(Debug)]
+pub struct FmOscillator {
+    algorithm: FmAlgorithm,
+    operators: Vec<Operator>,
+    frequency: f32
+}
+
+impl FmOscillator {
+    pub fn new(freq: f32, parameters: &FmParameters) -> FmOscillator {
+        let operators = parameters.operators.iter().map(|operator| Operator {
+            phase: PhaseAccumulator::new(freq * operator.ratio.clamp(MIN_FM_RATIO, MAX_FM_RATIO)),
+            parameters: *operator,
+            output: [0.0; 2]
+        }).collect();
+
+        FmOscillator { algorithm: parameters.algorithm, operators, frequency: freq }
+    }
+
+    /// Advances every operator by one sample and returns the sum of the carriers.
+    pub fn generate_fm(&mut self) -> f32 {
+        // Modulators always have a higher number than the operators they modulate, so evaluating the operators
+        // from the last to the first makes the output of every modulator available before it is used
+        for index in (0..self.algorithm.operator_count()).rev() {
+            let modulation: f32 = self.algorithm.modulators(index).iter()
+                .map(|&modulator| self.operators[modulator].output[0] * self.operators[modulator].parameters.index)
+                .sum();
+
+            let operator = &mut self.operators[index];
+
+            // Averaging the last two outputs tames the oscillation which strong feedback is otherwise prone to
+            let feedback = operator.parameters.feedback * PI * 0.5 * (operator.output[0] + operator.output[1]);
+            let output = (2.0 * PI * operator.phase.advance() + modulation + feedback).sin();
+
+            operator.output = [output, operator.output[0]];
+        }
+
+        let carriers = self.algorithm.carriers();
+        carriers.iter().map(|&carrier| self.operators[carrier].output[0]).sum::<f32>() / carriers.len() as f32
+    }
+}
+
+/// Implementation of the [Iterator] trait for the [FmOscillator]
+impl Iterator for FmOscillator {
+    type Item = f32;
+    fn next(&mut self) -> Option<f32> {
+        Some(self.generate_fm())
+    }
+}
+
+/// Implementation of the [Source] trait for the [FmOscillator]
+impl Source for FmOscillator {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        MONO
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE as u32
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Implementation of the [Oscillator] trait for the [FmOscillator]
+impl Oscillator for FmOscillator {
+    fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency;
+        for operator in self.operators.iter_mut() {
+            operator.phase.set_frequency(frequency * operator.parameters.ratio.clamp(MIN_FM_RATIO, MAX_FM_RATIO));
+        }
+    }
+
+    fn reset_phase(&mut self) {
+        for operator in self.operators.iter_mut() {
+            operator.phase.reset();
+        }
+    }
+
+    fn set_fm_parameters(&mut self, parameters: &FmParameters) {
+        self.algorithm = parameters.algorithm;
+        for (operator, operator_parameters) in self.operators.iter_mut().zip(parameters.operators.iter()) {
+            operator.parameters = *operator_parameters;
+        }
+
+        // Retune the operators to their new ratios, keeping their phases
+        self.set_frequency(self.frequency);
+    }
+}
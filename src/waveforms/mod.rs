@@ -1,25 +1,111 @@
-use std::fmt;
+use rodio::Source;
+
+use crate::waveforms::combiner::OscillatorCombiner;
+use crate::waveforms::fm::FmParameters;
 
 pub mod sine_wave;
 pub mod square_wave;
+pub mod sawtooth_wave;
+pub mod triangle_wave;
+pub mod pulse_wave;
+pub mod noise;
+pub mod poly_blep;
+pub mod phase_accumulator;
+pub mod wavetable;
+pub mod fm;
+pub mod registry;
+pub mod upmix;
+pub mod combiner;
 
 pub const MONO: u16 = 1;
+pub const STEREO: u16 = 2;
 pub const SAMPLE_RATE: f32 = 48000.0;
 pub const AMPLITUDE: f32 = 0.20;
-pub const DURATION: f32 = 0.19;
 
+/// Maximum coarse detune of the second oscillator in semitones, in either direction
+pub const MAX_COARSE_DETUNE: i32 = 24;
+
+/// Maximum fine detune of the second oscillator in cents, in either direction
+pub const MAX_FINE_DETUNE: i32 = 50;
+
+/// Maximum number of octaves the sub-oscillator may be tuned below the played note
+pub const MAX_SUB_OCTAVES: u32 = 2;
+
+/// A sound source whose pitch may be changed continuously while it is playing
+pub trait Oscillator: Source<Item=f32> + Send {
+    /// Changes the frequency of the oscillator in Hz without resetting its phase.
+    fn set_frequency(&mut self, frequency: f32);
+
+    /// Restarts the current period from the beginning, leaving the frequency unchanged.
+    fn reset_phase(&mut self);
+
+    /// Offsets the duty cycle the oscillator was created with, as done by modulation.
+    /// Oscillators without a duty cycle ignore the offset.
+    fn set_pulse_width_offset(&mut self, _offset: f32) {}
+
+    /// Moves the read position within a wavetable, in the range [0.0, 1.0], as done by the wavetable position knob
+    /// and by modulation. Oscillators without a wavetable ignore the position.
+    fn set_wavetable_position(&mut self, _position: f32) {}
+
+    /// Replaces the algorithm and the ratio, index and feedback of the operators of an FM oscillator, as done while
+    /// they are edited. Oscillators without operators ignore the settings.
+    fn set_fm_parameters(&mut self, _parameters: &FmParameters) {}
+
+    /// Returns the next sample, or silence once the oscillator has been exhausted.
+    fn next_sample(&mut self) -> f32 {
+        self.next().unwrap_or(0.0)
+    }
+}
+
+/// Settings of the second oscillator of every voice, relative to the first oscillator
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Waveform {
-    SINE,
-    SQUARE
+pub struct SecondOscillatorParameters {
+    /// Detune in semitones.
+    pub coarse: i32,
+    /// Detune in cents, on top of the coarse detune.
+    pub fine: i32,
+    /// Balance between the first oscillator alone at 0.0 and the oscillators fully combined at 1.0,
+    /// which for the mix combiner is the second oscillator alone.
+    pub mix: f32,
+    /// How the second oscillator is combined with the first one.
+    pub combiner: OscillatorCombiner,
+    /// Whether the second oscillator restarts its period whenever the first one does.
+    pub sync: bool
 }
 
-/// Implements the [Display] trait for [WaveForm]
-impl fmt::Display for Waveform {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Waveform::SINE => write!(f, "Sine"),
-            Waveform::SQUARE => write!(f, "Square")
-        }
+impl SecondOscillatorParameters {
+    /// Returns the factor the frequency of the first oscillator is multiplied with to get that of the second.
+    pub fn frequency_ratio(&self) -> f32 {
+        2.0_f32.powf((self.coarse as f32 + self.fine as f32 / 100.0) / 12.0)
     }
-}
\ No newline at end of file
+}
+
+impl Default for SecondOscillatorParameters {
+    fn default() -> Self {
+        SecondOscillatorParameters { coarse: 0, fine: 0, mix: 0.0, combiner: OscillatorCombiner::Mix, sync: false }
+    }
+}
+
+/// Settings of the sub-oscillator and noise layer of every voice, which are mixed in next to the oscillators
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SubNoiseParameters {
+    /// Number of octaves the sub-oscillator is tuned below the played note, either 1 or 2.
+    pub sub_octaves: u32,
+    /// Level of the sub-oscillator in the range [0.0, 1.0].
+    pub sub_level: f32,
+    /// Level of the noise generator in the range [0.0, 1.0].
+    pub noise_level: f32
+}
+
+impl SubNoiseParameters {
+    /// Returns the factor the frequency of the played note is multiplied with to get that of the sub-oscillator.
+    pub fn sub_frequency_ratio(&self) -> f32 {
+        0.5_f32.powi(self.sub_octaves as i32)
+    }
+}
+
+impl Default for SubNoiseParameters {
+    fn default() -> Self {
+        SubNoiseParameters { sub_octaves: 1, sub_level: 0.0, noise_level: 0.0 }
+    }
+}
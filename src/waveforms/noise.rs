@@ -0,0 +1,135 @@
+use rodio::Source;
+use std::time::Duration;
+use crate::{
+    waveforms::{Oscillator, MONO, SAMPLE_RATE}
+};
+
+/// Pseudo-random number generator based on Marsaglia's xorshift, which is cheap enough to run per sample
+#[derive(Debug)]
+struct Xorshift {
+    state: u32
+}
+
+impl Xorshift {
+    fn new(seed: u32) -> Xorshift {
+        // A zero state would only ever yield zeros
+        Xorshift { state: seed.max(1) }
+    }
+
+    /// Returns a uniformly distributed value in the range [-1.0, 1.0].
+    fn next_bipolar(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        (self.state as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+}
+
+/// Noise with equal energy at all frequencies
+#[derive(Debug)]
+pub struct WhiteNoise {
+    generator: Xorshift
+}
+
+impl WhiteNoise {
+    pub fn new(seed: u32) -> WhiteNoise {
+        WhiteNoise { generator: Xorshift::new(seed) }
+    }
+}
+
+/// Implementation of the [Iterator] trait for the [WhiteNoise]
+impl Iterator for WhiteNoise {
+    type Item = f32;
+    fn next(&mut self) -> Option<f32> {
+        Some(self.generator.next_bipolar())
+    }
+}
+
+/// Implementation of the [Source] trait for the [WhiteNoise]
+impl Source for WhiteNoise {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        MONO
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE as u32
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Implementation of the [Oscillator] trait for the [WhiteNoise], which has no pitch to change
+impl Oscillator for WhiteNoise {
+    fn set_frequency(&mut self, _frequency: f32) {}
+
+    fn reset_phase(&mut self) {}
+}
+
+/// Noise with equal energy per octave, falling off by 3 dB per octave.
+///
+/// White noise is shaped with Paul Kellet's refined filter, a parallel bank of one-pole low-pass filters
+/// approximating the -3 dB/octave slope within ±0.05 dB above 9.2 Hz at 44.1 kHz.
+#[derive(Debug)]
+pub struct PinkNoise {
+    generator: Xorshift,
+    b: [f32; 7]
+}
+
+impl PinkNoise {
+    pub fn new(seed: u32) -> PinkNoise {
+        PinkNoise { generator: Xorshift::new(seed), b: [0.0; 7] }
+    }
+}
+
+/// Implementation of the [Iterator] trait for the [PinkNoise]
+impl Iterator for PinkNoise {
+    type Item = f32;
+    fn next(&mut self) -> Option<f32> {
+        let white = self.generator.next_bipolar();
+        let b = &mut self.b;
+
+        b[0] = 0.99886 * b[0] + white * 0.0555179;
+        b[1] = 0.99332 * b[1] + white * 0.0750759;
+        b[2] = 0.96900 * b[2] + white * 0.153852;
+        b[3] = 0.86650 * b[3] + white * 0.3104856;
+        b[4] = 0.55000 * b[4] + white * 0.5329522;
+        b[5] = -0.7616 * b[5] - white * 0.0168980;
+        let pink = b[0] + b[1] + b[2] + b[3] + b[4] + b[5] + b[6] + white * 0.5362;
+        b[6] = white * 0.115926;
+
+        // Scale the sum of the filter bank back to roughly [-1.0, 1.0]
+        Some(pink * 0.11)
+    }
+}
+
+/// Implementation of the [Source] trait for the [PinkNoise]
+impl Source for PinkNoise {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        MONO
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE as u32
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Implementation of the [Oscillator] trait for the [PinkNoise], which has no pitch to change
+impl Oscillator for PinkNoise {
+    fn set_frequency(&mut self, _frequency: f32) {}
+
+    fn reset_phase(&mut self) {}
+}
@@ -0,0 +1,43 @@
+use crate::waveforms::SAMPLE_RATE;
+
+/// Tracks the position within the current period of an oscillator as a fraction in the range [0.0, 1.0).
+///
+/// Rather than computing the phase from the elapsed time, which loses precision as the time grows and jumps
+/// whenever the frequency changes, the phase is advanced by 'f / sample rate' per sample and wrapped around.
+/// The phase is thereby continuous under frequency changes, allowing glide, vibrato and pitch bend without clicks.
+#[derive(Debug, Clone, Copy)]
+pub struct PhaseAccumulator {
+    phase: f32,
+    increment: f32
+}
+
+impl PhaseAccumulator {
+    pub fn new(frequency: f32) -> PhaseAccumulator {
+        PhaseAccumulator { phase: 0.0, increment: frequency / SAMPLE_RATE }
+    }
+
+    /// Changes the frequency from the next sample onwards while keeping the current phase.
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.increment = frequency / SAMPLE_RATE;
+    }
+
+    /// Restarts the period from the beginning, as done by a hard-synced oscillator.
+    pub fn reset(&mut self) {
+        self.phase = 0.0;
+    }
+
+    /// Returns the phase advanced per sample, i.e. frequency / sample rate.
+    pub fn increment(&self) -> f32 {
+        self.increment
+    }
+
+    /// Returns the current phase and advances the accumulator by one sample.
+    pub fn advance(&mut self) -> f32 {
+        let phase = self.phase;
+
+        self.phase += self.increment;
+        self.phase -= self.phase.floor();
+
+        phase
+    }
+}
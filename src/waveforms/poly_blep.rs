@@ -0,0 +1,96 @@
+/// Polynomial band-limited step (PolyBLEP) residual for a discontinuity at phase 0.0.
+///
+/// A naive square or sawtooth jumps instantaneously, which requires infinitely many harmonics. Every harmonic
+/// above the Nyquist frequency folds back into the audible range as inharmonic noise, which is most audible
+/// for high notes. Subtracting this residual from the naive waveform replaces each jump with a
+/// two-sample polynomial approximation of a band-limited step, suppressing most of the folded-back energy.
+///
+/// # Arguments
+///
+/// * `phase` - The position within the period in the range [0.0, 1.0), with the discontinuity at 0.0.
+/// * `phase_increment` - The phase advanced per sample, i.e. frequency / sample rate.
+///
+/// # Returns
+///
+/// The correction to add for a rising step of height 2.0, which is zero outside of one sample around the discontinuity.
+pub fn poly_blep(phase: f32, phase_increment: f32) -> f32 {
+    if phase < phase_increment {
+        // The sample just after the discontinuity
+        let t = phase / phase_increment;
+        2.0 * t - t * t - 1.0
+    } else if phase > 1.0 - phase_increment {
+        // The sample just before the discontinuity
+        let t = (phase - 1.0) / phase_increment;
+        t * t + 2.0 * t + 1.0
+    } else {
+        0.0
+    }
+}
+
+/// Returns a band-limited pulse which is high for the fraction `width` of each period,
+/// with a rising edge at phase 0.0 and a falling edge at phase `width`.
+pub fn band_limited_pulse(phase: f32, phase_increment: f32, width: f32) -> f32 {
+    let naive = if phase < width { 1.0 } else { -1.0 };
+
+    // Smooth the rising edge at 0.0 and the falling edge at `width`
+    naive + poly_blep(phase, phase_increment) - poly_blep((phase + 1.0 - width).fract(), phase_increment)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use super::*;
+
+    /// Number of samples rendered and transformed
+    const LENGTH: usize = 2048;
+
+    /// Number of periods within the rendered samples, which puts the fundamental at about 1758 Hz at 48 kHz,
+    /// close to A in octave 6. As it shares no factor with the length, folded-back harmonics land between the harmonics.
+    const PERIODS: usize = 75;
+
+    /// Smallest reduction in decibels of the energy between the harmonics compared to the naive waveform
+    const MIN_ALIASING_REDUCTION: f64 = 10.0;
+
+    /// Renders a waveform from the phase of each sample, which completes exactly [PERIODS] periods.
+    fn render(waveform: impl Fn(f32, f32) -> f32) -> Vec<f32> {
+        let increment = PERIODS as f32 / LENGTH as f32;
+        (0..LENGTH).map(|n| waveform((n * PERIODS % LENGTH) as f32 / LENGTH as f32, increment)).collect()
+    }
+
+    /// Returns the energy of all DFT bins below Nyquist which are not a harmonic of the fundamental.
+    fn inharmonic_energy(samples: &[f32]) -> f64 {
+        (1..LENGTH / 2)
+            .filter(|bin| bin % PERIODS != 0)
+            .map(|bin| {
+                let (re, im) = samples.iter().enumerate().fold((0.0, 0.0), |(re, im), (n, &sample)| {
+                    let angle = TAU * (bin * n % LENGTH) as f64 / LENGTH as f64;
+                    (re + sample as f64 * angle.cos(), im - sample as f64 * angle.sin())
+                });
+                re * re + im * im
+            })
+            .sum()
+    }
+
+    /// Asserts that the band-limited waveform has clearly less energy between the harmonics than the naive one.
+    fn assert_less_aliasing(naive: &[f32], band_limited: &[f32]) {
+        let reduction = 10.0 * (inharmonic_energy(naive) / inharmonic_energy(band_limited)).log10();
+        assert!(reduction >= MIN_ALIASING_REDUCTION, "aliasing reduced by {:.1} dB only", reduction);
+    }
+
+    #[test]
+    fn poly_blep_reduces_aliasing_of_sawtooth() {
+        let naive = render(|phase, _| 2.0 * phase - 1.0);
+        let band_limited = render(|phase, increment| 2.0 * phase - 1.0 - poly_blep(phase, increment));
+
+        assert_less_aliasing(&naive, &band_limited);
+    }
+
+    #[test]
+    fn band_limited_pulse_reduces_aliasing_of_square() {
+        let naive = render(|phase, _| if phase < 0.5 { 1.0 } else { -1.0 });
+        let band_limited = render(|phase, increment| band_limited_pulse(phase, increment, 0.5));
+
+        assert_less_aliasing(&naive, &band_limited);
+    }
+}
@@ -0,0 +1,73 @@
+use rodio::Source;
+use std::time::Duration;
+use crate::{
+    waveforms::{
+        phase_accumulator::PhaseAccumulator,
+        poly_blep::band_limited_pulse,
+        Oscillator, MONO, SAMPLE_RATE
+}};
+
+pub const MIN_PULSE_WIDTH: f32 = 0.05;
+pub const MAX_PULSE_WIDTH: f32 = 0.95;
+pub const DEFAULT_PULSE_WIDTH: f32 = 0.25;
+
+#[derive(Debug)]
+pub struct PulseWave {
+    phase: PhaseAccumulator,
+    width: f32,
+    offset: f32
+}
+
+impl PulseWave {
+    /// Creates a pulse wave which is high for the given fraction (duty cycle) of each period.
+    pub fn new(freq: f32, width: f32) -> PulseWave {
+        PulseWave { phase: PhaseAccumulator::new(freq), width: width.clamp(MIN_PULSE_WIDTH, MAX_PULSE_WIDTH), offset: 0.0 }
+    }
+}
+
+/// Implementation of the [Iterator] trait for the [PulseWave]
+impl Iterator for PulseWave {
+    type Item = f32;
+    fn next(&mut self) -> Option<f32> {
+        // Stay high for the duty cycle of the period and low for the remainder, with band-limited edges
+        let increment = self.phase.increment();
+        let width = (self.width + self.offset).clamp(MIN_PULSE_WIDTH, MAX_PULSE_WIDTH);
+        let pulse_wave = band_limited_pulse(self.phase.advance(), increment, width);
+
+        Some(pulse_wave)
+    }
+}
+
+/// Implementation of the [Source] trait for the [PulseWave]
+impl Source for PulseWave {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        MONO
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE as u32
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Implementation of the [Oscillator] trait for the [PulseWave]
+impl Oscillator for PulseWave {
+    fn set_frequency(&mut self, frequency: f32) {
+        self.phase.set_frequency(frequency);
+    }
+
+    fn reset_phase(&mut self) {
+        self.phase.reset();
+    }
+
+    fn set_pulse_width_offset(&mut self, offset: f32) {
+        self.offset = offset;
+    }
+}
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use crate::graphics::constants::*;
+use crate::waveforms::{
+    fm::{FmOscillator, FmParameters},
+    noise::{PinkNoise, WhiteNoise},
+    pulse_wave::PulseWave,
+    sawtooth_wave::SawtoothWave,
+    sine_wave::SineWave,
+    square_wave::SquareWave,
+    triangle_wave::TriangleWave,
+    wavetable::{Wavetable, WavetableOscillator},
+    Oscillator
+};
+
+/// The settings an oscillator is created from when a note is started
+pub struct OscillatorSettings<'a> {
+    pub frequency: f32,
+    pub pulse_width: f32,
+    pub wavetable: Option<&'a Arc<Wavetable>>,
+    pub wavetable_position: f32,
+    pub fm: FmParameters,
+    pub seed: u32
+}
+
+/// Describes how the waveform display shows a waveform, where each variant holds the index of a sprite map,
+/// see [crate::graphics::sprites::Sprites::displays]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WaveformDisplay {
+    /// The animation frames of the sprite map draw the waveform.
+    Sprite(usize),
+    /// The sprite map only holds the background, onto which a period rendered by the oscillator itself is traced.
+    /// This suits waveforms whose shape depends on their settings.
+    Traced(usize)
+}
+
+/// Describes a waveform which may be selected on the synthesizer, how it is shown and how to create an oscillator playing it
+pub struct WaveformDefinition {
+    /// The name of the waveform as shown on the waveform display, unless the label replaces it.
+    pub name: &'static str,
+    /// How the waveform display shows the waveform.
+    pub display: WaveformDisplay,
+    /// Whether the waveform plays a loaded wavetable, in which case it is skipped if no wavetables were loaded.
+    pub requires_wavetable: bool,
+    create: fn(&OscillatorSettings) -> Box<dyn Oscillator>,
+    label: fn(&OscillatorSettings) -> Option<String>
+}
+
+impl WaveformDefinition {
+    /// Creates an oscillator playing this waveform.
+    pub fn create(&self, settings: &OscillatorSettings) -> Box<dyn Oscillator> {
+        (self.create)(settings)
+    }
+
+    /// Returns the name shown on the waveform display, which may depend on the settings, e.g. the name of the played
+    /// wavetable, and is the name of the waveform otherwise.
+    pub fn label(&self, settings: &OscillatorSettings) -> String {
+        (self.label)(settings).unwrap_or_else(|| self.name.to_string())
+    }
+}
+
+/// Every waveform available on the synthesizer, in the order they are cycled through with the 'S' key.
+///
+/// New waveforms are added by implementing [Oscillator] and appending a definition here.
+pub static WAVEFORMS: [WaveformDefinition; 9] = [
+    WaveformDefinition {
+        name: "Sine",
+        display: WaveformDisplay::Sprite(WAVEFORM_SINE),
+        requires_wavetable: false,
+        create: |settings| Box::new(SineWave::new(settings.frequency)),
+        label: |_| None
+    },
+    WaveformDefinition {
+        name: "Square",
+        display: WaveformDisplay::Sprite(WAVEFORM_SQUARE),
+        requires_wavetable: false,
+        create: |settings| Box::new(SquareWave::new(settings.frequency)),
+        label: |_| None
+    },
+    WaveformDefinition {
+        name: "Sawtooth",
+        display: WaveformDisplay::Sprite(WAVEFORM_SAWTOOTH),
+        requires_wavetable: false,
+        create: |settings| Box::new(SawtoothWave::new(settings.frequency)),
+        label: |_| None
+    },
+    WaveformDefinition {
+        name: "Triangle",
+        display: WaveformDisplay::Sprite(WAVEFORM_TRIANGLE),
+        requires_wavetable: false,
+        create: |settings| Box::new(TriangleWave::new(settings.frequency)),
+        label: |_| None
+    },
+    WaveformDefinition {
+        name: "Pulse",
+        display: WaveformDisplay::Sprite(WAVEFORM_PULSE),
+        requires_wavetable: false,
+        create: |settings| Box::new(PulseWave::new(settings.frequency, settings.pulse_width)),
+        label: |_| None
+    },
+    WaveformDefinition {
+        name: "White noise",
+        display: WaveformDisplay::Sprite(WAVEFORM_WHITE_NOISE),
+        requires_wavetable: false,
+        create: |settings| Box::new(WhiteNoise::new(settings.seed)),
+        label: |_| None
+    },
+    WaveformDefinition {
+        name: "Pink noise",
+        display: WaveformDisplay::Sprite(WAVEFORM_PINK_NOISE),
+        requires_wavetable: false,
+        create: |settings| Box::new(PinkNoise::new(settings.seed)),
+        label: |_| None
+    },
+    WaveformDefinition {
+        name: "Wavetable",
+        display: WaveformDisplay::Traced(WAVEFORM_WAVETABLE),
+        requires_wavetable: true,
+        create: |settings| match settings.wavetable {
+            Some(table) => Box::new(WavetableOscillator::new(Arc::clone(table), settings.frequency, settings.wavetable_position)),
+            None => Box::new(SineWave::new(settings.frequency))
+        },
+        label: |settings| settings.wavetable.map(|table| table.name().to_string())
+    },
+    WaveformDefinition {
+        name: "FM",
+        display: WaveformDisplay::Traced(WAVEFORM_FM),
+        requires_wavetable: false,
+        create: |settings| Box::new(FmOscillator::new(settings.frequency, &settings.fm)),
+        label: |settings| Some(settings.fm.algorithm.to_string())
+    }
+];
@@ -0,0 +1,65 @@
+use rodio::Source;
+use std::time::Duration;
+use crate::{
+    waveforms::{
+        phase_accumulator::PhaseAccumulator,
+        poly_blep::poly_blep,
+        Oscillator, MONO, SAMPLE_RATE
+}};
+
+#[derive(Debug)]
+pub struct SawtoothWave {
+    phase: PhaseAccumulator
+}
+
+impl SawtoothWave {
+    pub fn new(freq: f32) -> SawtoothWave {
+        SawtoothWave { phase: PhaseAccumulator::new(freq) }
+    }
+}
+
+/// Implementation of the [Iterator] trait for the [SawtoothWave]
+impl Iterator for SawtoothWave {
+    type Item = f32;
+    fn next(&mut self) -> Option<f32> {
+        // Ramp linearly from -1.0 to 1.0 over each period
+        let increment = self.phase.increment();
+        let phase = self.phase.advance();
+        let naive_sawtooth = 2.0 * phase - 1.0;
+
+        // Band-limit the falling edge at the end of each period to avoid aliasing
+        let sawtooth_wave = naive_sawtooth - poly_blep(phase, increment);
+
+        Some(sawtooth_wave)
+    }
+}
+
+/// Implementation of the [Source] trait for the [SawtoothWave]
+impl Source for SawtoothWave {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        MONO
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE as u32
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Implementation of the [Oscillator] trait for the [SawtoothWave]
+impl Oscillator for SawtoothWave {
+    fn set_frequency(&mut self, frequency: f32) {
+        self.phase.set_frequency(frequency);
+    }
+
+    fn reset_phase(&mut self) {
+        self.phase.reset();
+    }
+}
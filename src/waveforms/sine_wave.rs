@@ -1,21 +1,22 @@
 use rodio::Source;
 use std::{f32::consts::PI, time::Duration};
 use crate::{
-    waveforms::{MONO, SAMPLE_RATE}
-};
+    waveforms::{
+        phase_accumulator::PhaseAccumulator,
+        Oscillator, MONO, SAMPLE_RATE
+}};
 
 #[derive(Debug)]
 pub struct SineWave {
-    freq: f32,
-    num_sample: usize
+    phase: PhaseAccumulator
 }
 
 impl SineWave {
     pub fn new(freq: f32) -> SineWave {
-        SineWave { freq, num_sample: 0}
+        SineWave { phase: PhaseAccumulator::new(freq) }
     }
     pub fn generate_sine_wave(&mut self) -> f32 {
-        calculate_sine(self.freq, self.num_sample)
+        calculate_sine(self.phase.advance())
     }
 }
 
@@ -23,9 +24,6 @@ impl SineWave {
 impl Iterator for SineWave {
     type Item = f32;
     fn next(&mut self) -> Option<f32> {
-        // increment sample counter by 1
-        self.num_sample = self.num_sample.wrapping_add(1);
-
         // Generates a sine wave
         let sine_wave = self.generate_sine_wave();
 
@@ -52,15 +50,20 @@ impl Source for SineWave {
     }
 }
 
-///  The formula for calculating a sine wave is 'y(t) = sin(2πft)', whereby:
-/// '2πf' is two times pi the frequency (ie 2 * 3.14~ * 440 hz)
-/// 't' is time in seconds in relation to the sample rate (1/48k = 2.08333×10−5 seconds)
-pub fn calculate_sine(frequency: f32, num_sample: usize) -> f32 {
-    // Calculate time in seconds based on the sample number and the sample rate
-    let time: f32 = num_sample as f32 / SAMPLE_RATE;
-    // Calculate angular frequency (2πf)
-    let angular_frequency: f32 = 2.0 * PI * frequency;
+/// Implementation of the [Oscillator] trait for the [SineWave]
+impl Oscillator for SineWave {
+    fn set_frequency(&mut self, frequency: f32) {
+        self.phase.set_frequency(frequency);
+    }
+
+    fn reset_phase(&mut self) {
+        self.phase.reset();
+    }
+}
 
-    // Calculate the sine wave value using the angular frequency and time
-    (angular_frequency * time).sin()
-}
\ No newline at end of file
+///  The formula for calculating a sine wave is 'y = sin(2πφ)', whereby:
+/// 'φ' is the phase, i.e. the position within the current period in the range [0.0, 1.0)
+/// '2πφ' is thereby the angle in radians, completing a full circle once per period
+pub fn calculate_sine(phase: f32) -> f32 {
+    (2.0 * PI * phase).sin()
+}
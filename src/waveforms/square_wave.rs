@@ -2,19 +2,19 @@ use rodio::Source;
 use std::time::Duration;
 use crate::{
     waveforms::{
-        sine_wave::calculate_sine,
-        MONO, SAMPLE_RATE
+        phase_accumulator::PhaseAccumulator,
+        poly_blep::band_limited_pulse,
+        Oscillator, MONO, SAMPLE_RATE
 }};
 
 #[derive(Debug)]
 pub struct SquareWave {
-    freq: f32,
-    num_sample: usize
+    phase: PhaseAccumulator
 }
 
 impl SquareWave {
     pub fn new(freq: f32) -> SquareWave {
-        SquareWave { freq, num_sample: 0 }
+        SquareWave { phase: PhaseAccumulator::new(freq) }
     }
 }
 
@@ -22,14 +22,10 @@ impl SquareWave {
 impl Iterator for SquareWave {
     type Item = f32;
     fn next(&mut self) -> Option<f32> {
-        // increment sample counter by 1
-        self.num_sample = self.num_sample.wrapping_add(1);
-
-        // Generates a sine wave
-        let sine_wave: f32 = calculate_sine(self.freq, self.num_sample);
-
-        // Utilize a sign function to normalize our sine wave to [1.0 or -1.0]
-        let square_wave: f32 = sgn(sine_wave);
+        // A square wave is a pulse wave which is high for exactly half of each period. Rather than taking the sign
+        // of a sine wave, the edges are band-limited to avoid aliasing in the upper octaves
+        let increment = self.phase.increment();
+        let square_wave: f32 = band_limited_pulse(self.phase.advance(), increment, 0.5);
 
         Some(square_wave)
     }
@@ -54,15 +50,13 @@ impl Source for SquareWave {
     }
 }
 
-/// Returns the sign of the given floating-point number.
-///
-/// The signum function returns:
-/// - 1.0 if the number is positive,
-/// - -1.0 if the number is negative,
-///
-/// # Arguments
-///
-/// * `x` - The floating-point number for which to determine the sign.
-fn sgn(x: f32) -> f32 {
-    x.signum()
-}
\ No newline at end of file
+/// Implementation of the [Oscillator] trait for the [SquareWave]
+impl Oscillator for SquareWave {
+    fn set_frequency(&mut self, frequency: f32) {
+        self.phase.set_frequency(frequency);
+    }
+
+    fn reset_phase(&mut self) {
+        self.phase.reset();
+    }
+}
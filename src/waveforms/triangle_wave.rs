@@ -0,0 +1,60 @@
+use rodio::Source;
+use std::time::Duration;
+use crate::{
+    waveforms::{
+        phase_accumulator::PhaseAccumulator,
+        Oscillator, MONO, SAMPLE_RATE
+}};
+
+#[derive(Debug)]
+pub struct TriangleWave {
+    phase: PhaseAccumulator
+}
+
+impl TriangleWave {
+    pub fn new(freq: f32) -> TriangleWave {
+        TriangleWave { phase: PhaseAccumulator::new(freq) }
+    }
+}
+
+/// Implementation of the [Iterator] trait for the [TriangleWave]
+impl Iterator for TriangleWave {
+    type Item = f32;
+    fn next(&mut self) -> Option<f32> {
+        // Fold the phase into a ramp rising from -1.0 to 1.0 during the first half period and falling back during the second
+        let phase = self.phase.advance();
+        let triangle_wave = 1.0 - 4.0 * (phase - 0.5).abs();
+
+        Some(triangle_wave)
+    }
+}
+
+/// Implementation of the [Source] trait for the [TriangleWave]
+impl Source for TriangleWave {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        MONO
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE as u32
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Implementation of the [Oscillator] trait for the [TriangleWave]
+impl Oscillator for TriangleWave {
+    fn set_frequency(&mut self, frequency: f32) {
+        self.phase.set_frequency(frequency);
+    }
+
+    fn reset_phase(&mut self) {
+        self.phase.reset();
+    }
+}
@@ -0,0 +1,32 @@
+/// Places the samples of a mono source, such as any [crate::waveforms::Oscillator], in the stereo field.
+///
+/// Oscillators are rendered in mono, as reported by their `channels()`, and are only spread onto the
+/// left and right channel once they enter the stereo signal path of a voice.
+#[derive(Debug, Clone, Copy)]
+pub struct Upmix {
+    left_gain: f32,
+    right_gain: f32
+}
+
+impl Upmix {
+    pub fn new(pan: f32) -> Self {
+        let mut upmix = Upmix { left_gain: 1.0, right_gain: 1.0 };
+        upmix.set_pan(pan);
+        upmix
+    }
+
+    /// Moves the signal from hard left at -1.0 over the center at 0.0 to hard right at 1.0.
+    ///
+    /// A balance law is used, so a centered signal is passed to both channels at full level and panning
+    /// only ever attenuates the opposite channel.
+    pub fn set_pan(&mut self, pan: f32) {
+        let pan = pan.clamp(-1.0, 1.0);
+        self.left_gain = (1.0 - pan).min(1.0);
+        self.right_gain = (1.0 + pan).min(1.0);
+    }
+
+    /// Returns the left and right sample of a mono sample.
+    pub fn process(&self, sample: f32) -> (f32, f32) {
+        (sample * self.left_gain, sample * self.right_gain)
+    }
+}
@@ -0,0 +1,297 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use hound::{SampleFormat, WavReader};
+use rodio::Source;
+
+use crate::waveforms::{
+    phase_accumulator::PhaseAccumulator,
+    Oscillator, MONO, SAMPLE_RATE
+};
+
+/// Number of samples in each single-cycle frame of a wavetable
+pub const WAVETABLE_FRAME_SIZE: usize = 2048;
+
+/// A named sequence of single-cycle waveforms (frames) which an oscillator may morph between
+#[derive(Debug)]
+pub struct Wavetable {
+    name: String,
+    frames: Vec<Vec<f32>>
+}
+
+impl Wavetable {
+    /// Reads a wavetable from a WAV file.
+    ///
+    /// The file is expected to hold consecutive single-cycle frames of [WAVETABLE_FRAME_SIZE] samples each, which is
+    /// the layout most wavetable editors export. A file shorter than one frame is treated as a single cycle and
+    /// stretched to the frame size. Only the first channel of multichannel files is used.
+    ///
+    /// # Parameters
+    /// - `path`: The path of the WAV file.
+    ///
+    /// # Returns
+    /// The wavetable named after the file stem, or the error encountered while decoding the file.
+    pub fn load(path: &Path) -> Result<Wavetable, hound::Error> {
+        let mut reader = WavReader::open(path)?;
+        let spec = reader.spec();
+        let channels = spec.channels as usize;
+
+        // Normalize integer samples to [-1.0, 1.0] regardless of their bit depth
+        let samples: Vec<f32> = match spec.sample_format {
+            SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+            SampleFormat::Int => {
+                // Reject bit depths a sample cannot be scaled by, as a malformed header would otherwise overflow the shift
+                if !(1..=32).contains(&spec.bits_per_sample) {
+                    return Err(hound::Error::Unsupported);
+                }
+
+                let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+                reader.samples::<i32>()
+                    .map(|sample| sample.map(|s| s as f32 / scale))
+                    .collect::<Result<_, _>>()?
+            }
+        };
+
+        // Keep the first channel only
+        let samples: Vec<f32> = samples.into_iter().step_by(channels.max(1)).collect();
+
+        if samples.is_empty() {
+            return Err(hound::Error::FormatError("wavetable contains no samples"));
+        }
+
+        let frames = if samples.len() < WAVETABLE_FRAME_SIZE {
+            vec![stretch_cycle(&samples)]
+        } else {
+            samples.chunks_exact(WAVETABLE_FRAME_SIZE).map(|frame| frame.to_vec()).collect()
+        };
+
+        let name = path.file_stem().map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+
+        Ok(Wavetable { name, frames })
+    }
+
+    /// Returns the name of the wavetable, taken from its file name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Reads the wavetable at a position and phase, both in the range [0.0, 1.0].
+    ///
+    /// The position selects where to read between the first and last frame, crossfading between the two nearest frames,
+    /// while the phase selects where to read within the cycle, interpolating linearly between the two nearest samples.
+    pub fn sample(&self, position: f32, phase: f32) -> f32 {
+        let frame_position = position.clamp(0.0, 1.0) * (self.frames.len() - 1) as f32;
+        let frame_index = frame_position.floor() as usize;
+        let next_frame_index = (frame_index + 1).min(self.frames.len() - 1);
+        let frame_fraction = frame_position - frame_index as f32;
+
+        let current = read_cycle(&self.frames[frame_index], phase);
+        let next = read_cycle(&self.frames[next_frame_index], phase);
+
+        current + (next - current) * frame_fraction
+    }
+}
+
+/// Reads a single-cycle frame at a phase in the range [0.0, 1.0), interpolating linearly and wrapping around at the end.
+fn read_cycle(frame: &[f32], phase: f32) -> f32 {
+    let position = phase * frame.len() as f32;
+    let index = position.floor() as usize % frame.len();
+    let next_index = (index + 1) % frame.len();
+    let fraction = position.fract();
+
+    frame[index] + (frame[next_index] - frame[index]) * fraction
+}
+
+/// Stretches a single cycle of arbitrary length to [WAVETABLE_FRAME_SIZE] samples.
+fn stretch_cycle(cycle: &[f32]) -> Vec<f32> {
+    (0..WAVETABLE_FRAME_SIZE)
+        .map(|i| read_cycle(cycle, i as f32 / WAVETABLE_FRAME_SIZE as f32))
+        .collect()
+}
+
+/// Loads every WAV file in a directory as a wavetable, ordered by file name.
+///
+/// Files which cannot be decoded are reported and skipped, so a single broken file does not prevent start-up.
+///
+/// # Parameters
+/// - `directory`: The path of the directory holding the WAV files.
+///
+/// # Returns
+/// The loaded wavetables, which is empty if the directory does not exist.
+pub fn load_wavetables(directory: &str) -> Vec<Arc<Wavetable>> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        println!("No wavetables loaded, as directory {} could not be read\n", directory);
+        return Vec::new();
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("wav")))
+        .collect();
+    paths.sort();
+
+    let mut wavetables = Vec::new();
+    for path in paths {
+        match Wavetable::load(&path) {
+            Ok(wavetable) => {
+                println!("Wavetable loaded from {} with {} frames", path.display(), wavetable.frames.len());
+                wavetables.push(Arc::new(wavetable));
+            }
+            Err(error) => println!("Failed to load wavetable from {}: {}", path.display(), error)
+        }
+    }
+
+    println!("Total wavetables loaded: {}\n", wavetables.len());
+
+    wavetables
+}
+
+#[derive(Debug)]
+pub struct WavetableOscillator {
+    table: Arc<Wavetable>,
+    phase: PhaseAccumulator,
+    position: f32
+}
+
+impl WavetableOscillator {
+    /// Creates an oscillator reading the wavetable at the given position in the range [0.0, 1.0].
+    pub fn new(table: Arc<Wavetable>, freq: f32, position: f32) -> WavetableOscillator {
+        WavetableOscillator { table, phase: PhaseAccumulator::new(freq), position }
+    }
+}
+
+/// Implementation of the [Iterator] trait for the [WavetableOscillator]
+impl Iterator for WavetableOscillator {
+    type Item = f32;
+    fn next(&mut self) -> Option<f32> {
+        Some(self.table.sample(self.position, self.phase.advance()))
+    }
+}
+
+/// Implementation of the [Source] trait for the [WavetableOscillator]
+impl Source for WavetableOscillator {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        MONO
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE as u32
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Implementation of the [Oscillator] trait for the [WavetableOscillator]
+impl Oscillator for WavetableOscillator {
+    fn set_frequency(&mut self, frequency: f32) {
+        self.phase.set_frequency(frequency);
+    }
+
+    fn reset_phase(&mut self) {
+        self.phase.reset();
+    }
+
+    fn set_wavetable_position(&mut self, position: f32) {
+        self.position = position;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use hound::{WavSpec, WavWriter};
+
+    use super::*;
+
+    /// Returns a path in the temporary directory which is unique to the test process and the given name.
+    fn temporary_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("wavetable-test-{}-{}.wav", std::process::id(), name))
+    }
+
+    /// Loads a wavetable from a file written to the temporary directory, removing the file again.
+    fn load_written(name: &str, write: impl FnOnce(&Path)) -> Result<Wavetable, hound::Error> {
+        let path = temporary_path(name);
+        write(&path);
+        let wavetable = Wavetable::load(&path);
+        fs::remove_file(&path).unwrap();
+        wavetable
+    }
+
+    #[test]
+    fn rejects_unsupported_bit_depth() {
+        // WAVE_FORMAT_EXTENSIBLE header of a mono integer file with 64-bit containers holding 40 valid bits
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(4 + 8 + 40 + 8 + 8_u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&40_u32.to_le_bytes());
+        bytes.extend_from_slice(&0xFFFE_u16.to_le_bytes());
+        bytes.extend_from_slice(&1_u16.to_le_bytes());
+        bytes.extend_from_slice(&48000_u32.to_le_bytes());
+        bytes.extend_from_slice(&(48000 * 8_u32).to_le_bytes());
+        bytes.extend_from_slice(&8_u16.to_le_bytes());
+        bytes.extend_from_slice(&64_u16.to_le_bytes());
+        bytes.extend_from_slice(&22_u16.to_le_bytes());
+        bytes.extend_from_slice(&40_u16.to_le_bytes());
+        bytes.extend_from_slice(&0_u32.to_le_bytes());
+        bytes.extend_from_slice(&[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71]);
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&8_u32.to_le_bytes());
+        bytes.extend_from_slice(&[0; 8]);
+
+        let wavetable = load_written("bit-depth", |path| fs::write(path, &bytes).unwrap());
+
+        assert!(matches!(wavetable, Err(hound::Error::Unsupported)));
+    }
+
+    #[test]
+    fn uses_first_channel_of_multichannel_files() {
+        let left: Vec<i16> = (0..2 * WAVETABLE_FRAME_SIZE).map(|i| (i as i16 - 2048) * 8).collect();
+
+        let wavetable = load_written("multichannel", |path| {
+            let spec = WavSpec { channels: 2, sample_rate: 48000, bits_per_sample: 16, sample_format: SampleFormat::Int };
+            let mut writer = WavWriter::create(path, spec).unwrap();
+            for &sample in left.iter() {
+                writer.write_sample(sample).unwrap();
+                writer.write_sample(i16::MAX).unwrap();
+            }
+            writer.finalize().unwrap();
+        }).unwrap();
+
+        assert_eq!(wavetable.frames.len(), 2);
+        for (loaded, written) in wavetable.frames.concat().iter().zip(left.iter()) {
+            assert_eq!(*loaded, *written as f32 / 32768.0);
+        }
+    }
+
+    #[test]
+    fn stretches_short_cycle_to_frame_size() {
+        let wavetable = load_written("short-cycle", |path| {
+            let spec = WavSpec { channels: 1, sample_rate: 48000, bits_per_sample: 32, sample_format: SampleFormat::Float };
+            let mut writer = WavWriter::create(path, spec).unwrap();
+            for sample in [0.0_f32, 1.0, 0.0, -1.0] {
+                writer.write_sample(sample).unwrap();
+            }
+            writer.finalize().unwrap();
+        }).unwrap();
+
+        assert_eq!(wavetable.frames.len(), 1);
+        let frame = &wavetable.frames[0];
+        assert_eq!(frame.len(), WAVETABLE_FRAME_SIZE);
+
+        // Each of the four samples spans a quarter of the frame, interpolated linearly towards the next one
+        let quarter = WAVETABLE_FRAME_SIZE / 4;
+        assert_eq!([frame[0], frame[quarter], frame[2 * quarter], frame[3 * quarter]], [0.0, 1.0, 0.0, -1.0]);
+        assert_eq!(frame[quarter / 2], 0.5);
+        assert_eq!(frame[7 * quarter / 2], -0.5);
+    }
+}